@@ -0,0 +1,226 @@
+use crate::db::{Db, RegisterError};
+use crate::errors::JsonRegisterError;
+use crate::events::Event;
+
+/// Maps a `RegisterError` onto the right `JsonRegisterError` variant,
+/// keeping `RegisterError::HashMismatch` and `RegisterError::Corruption`
+/// distinguishable from a `tokio_postgres::Error` instead of collapsing all
+/// three into `RuntimeError`. Used by `Db`'s `register_one`/`get_object`
+/// impls below, and by `writer.rs`, whose buffered writer calls `Db` directly.
+pub(crate) fn map_register_err(e: &RegisterError) -> JsonRegisterError {
+    match e {
+        RegisterError::Postgres(e) => JsonRegisterError::RuntimeError(e.to_string()),
+        RegisterError::HashMismatch(msg) => JsonRegisterError::Integrity(msg.clone()),
+        RegisterError::Corruption(msg) => JsonRegisterError::Integrity(msg.clone()),
+    }
+}
+
+/// The persistence operations `Register` needs from a storage backend.
+///
+/// Extracting this trait lets `Register` be generic over how JSON objects are
+/// actually stored: the default `Db` backend talks to PostgreSQL, but tests
+/// (or future backends such as SQLite) can plug in something else entirely,
+/// the same split atuin makes between `atuin-server-database` (trait) and
+/// `atuin-server-postgres` (impl). All methods operate on
+/// already-canonicalised JSON strings — `Register` owns canonicalisation and
+/// caching, the backend only owns persistence. The pool/query-count
+/// introspection methods below are what feeds `TelemetryMetrics`, so any
+/// backend gets metrics support for free via its defaults.
+pub trait RegisterBackend: Send + Sync {
+    /// Registers a single canonicalised JSON string, returning its id.
+    ///
+    /// Must be idempotent: registering the same canonical string twice
+    /// returns the same id both times.
+    fn register_one(
+        &self,
+        canonical: &str,
+    ) -> impl std::future::Future<Output = Result<i32, JsonRegisterError>> + Send;
+
+    /// Registers a batch of canonicalised JSON strings, returning ids aligned
+    /// 1:1 with the input.
+    fn register_batch(
+        &self,
+        canonicals: &[String],
+    ) -> impl std::future::Future<Output = Result<Vec<i32>, JsonRegisterError>> + Send;
+
+    /// Looks up a canonicalised JSON string's id without registering it if absent.
+    fn lookup_id(
+        &self,
+        canonical: &str,
+    ) -> impl std::future::Future<Output = Result<Option<i32>, JsonRegisterError>> + Send;
+
+    /// The current size of the connection pool, if the backend has one.
+    ///
+    /// Defaults to 0 for backends with no pool concept.
+    fn pool_size(&self) -> usize {
+        0
+    }
+
+    /// The number of idle connections, if the backend has a pool.
+    ///
+    /// Defaults to 0 for backends with no pool concept.
+    fn idle_connections(&self) -> usize {
+        0
+    }
+
+    /// Whether the backend's connection pool is closed.
+    ///
+    /// Defaults to `false` for backends with no pool concept.
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    /// The total number of persistence queries/operations executed.
+    ///
+    /// Defaults to 0 for backends that don't track this.
+    fn queries_executed(&self) -> u64 {
+        0
+    }
+
+    /// The total number of failed persistence queries/operations.
+    ///
+    /// Defaults to 0 for backends that don't track this.
+    fn query_errors(&self) -> u64 {
+        0
+    }
+
+    /// Ensures the backend's schema (tables, indexes, migration metadata)
+    /// exists, creating it if necessary. Idempotent.
+    ///
+    /// Defaults to a no-op for backends with no schema concept, such as
+    /// `InMemoryBackend`.
+    fn ensure_schema(&self) -> impl std::future::Future<Output = Result<(), JsonRegisterError>> + Send {
+        async { Ok(()) }
+    }
+
+    /// Fetches a previously registered object's canonical JSON string by id.
+    ///
+    /// Defaults to `None` for backends with no retrieval support.
+    fn get_object(
+        &self,
+        _id: i32,
+    ) -> impl std::future::Future<Output = Result<Option<String>, JsonRegisterError>> + Send {
+        async { Ok(None) }
+    }
+
+    /// Fetches a page of the append-only registration event log after `seq`.
+    ///
+    /// Defaults to an empty page for backends with no event log concept.
+    fn events_since(
+        &self,
+        _seq: i64,
+        _page_size: i64,
+    ) -> impl std::future::Future<Output = Result<Vec<Event>, JsonRegisterError>> + Send {
+        async { Ok(Vec::new()) }
+    }
+}
+
+impl RegisterBackend for Db {
+    async fn register_one(&self, canonical: &str) -> Result<i32, JsonRegisterError> {
+        self.register_object(canonical)
+            .await
+            .map_err(|e| map_register_err(&e))
+    }
+
+    async fn register_batch(&self, canonicals: &[String]) -> Result<Vec<i32>, JsonRegisterError> {
+        self.register_batch_objects(canonicals)
+            .await
+            .map_err(|e| map_register_err(&e))
+    }
+
+    async fn lookup_id(&self, canonical: &str) -> Result<Option<i32>, JsonRegisterError> {
+        self.lookup_object(canonical)
+            .await
+            .map_err(|e| JsonRegisterError::RuntimeError(e.to_string()))
+    }
+
+    fn pool_size(&self) -> usize {
+        Db::pool_size(self)
+    }
+
+    fn idle_connections(&self) -> usize {
+        Db::idle_connections(self)
+    }
+
+    fn is_closed(&self) -> bool {
+        Db::is_closed(self)
+    }
+
+    fn queries_executed(&self) -> u64 {
+        Db::queries_executed(self)
+    }
+
+    fn query_errors(&self) -> u64 {
+        Db::query_errors(self)
+    }
+
+    async fn ensure_schema(&self) -> Result<(), JsonRegisterError> {
+        Db::ensure_schema(self)
+            .await
+            .map_err(|e| JsonRegisterError::RuntimeError(e.to_string()))
+    }
+
+    async fn get_object(&self, id: i32) -> Result<Option<String>, JsonRegisterError> {
+        Db::get_object(self, id)
+            .await
+            .map_err(|e| map_register_err(&e))
+    }
+
+    async fn events_since(&self, seq: i64, page_size: i64) -> Result<Vec<Event>, JsonRegisterError> {
+        Db::events_since(self, seq, page_size)
+            .await
+            .map_err(|e| JsonRegisterError::RuntimeError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::RegisterError;
+
+    #[test]
+    fn test_hash_mismatch_maps_to_integrity_not_runtime_error() {
+        let err = map_register_err(&RegisterError::HashMismatch("stored payload differs".into()));
+        assert!(matches!(err, JsonRegisterError::Integrity(msg) if msg == "stored payload differs"));
+    }
+
+    #[test]
+    fn test_postgres_error_maps_to_runtime_error() {
+        let err = map_register_err(&RegisterError::Postgres(
+            tokio_postgres::Error::__private_api_timeout(),
+        ));
+        assert!(matches!(err, JsonRegisterError::RuntimeError(_)));
+    }
+
+    #[test]
+    fn test_hash_mismatch_and_postgres_error_map_to_different_variants() {
+        let hash_err =
+            map_register_err(&RegisterError::HashMismatch("stored payload differs".into()));
+        let pg_err = map_register_err(&RegisterError::Postgres(
+            tokio_postgres::Error::__private_api_timeout(),
+        ));
+        assert_ne!(
+            std::mem::discriminant(&hash_err),
+            std::mem::discriminant(&pg_err)
+        );
+    }
+
+    #[test]
+    fn test_corruption_maps_to_integrity_not_runtime_error() {
+        let err = map_register_err(&RegisterError::Corruption("stored payload failed to decrypt".into()));
+        assert!(matches!(err, JsonRegisterError::Integrity(msg) if msg == "stored payload failed to decrypt"));
+    }
+
+    #[test]
+    fn test_corruption_and_postgres_error_map_to_different_variants() {
+        let corruption_err =
+            map_register_err(&RegisterError::Corruption("stored payload failed to decrypt".into()));
+        let pg_err = map_register_err(&RegisterError::Postgres(
+            tokio_postgres::Error::__private_api_timeout(),
+        ));
+        assert_ne!(
+            std::mem::discriminant(&corruption_err),
+            std::mem::discriminant(&pg_err)
+        );
+    }
+}
@@ -1,39 +1,183 @@
 use lru::LruCache;
+use std::collections::HashMap;
 use std::num::NonZeroUsize;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The default TTL applied by `Cache::with_negative_caching`, chosen to be
+/// short enough that a concurrently-inserted id isn't masked for long.
+const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(30);
+
+/// The default capacity of the negative-entry set. Negative entries are
+/// short-lived by design (see `DEFAULT_NEGATIVE_TTL`), so this only needs to
+/// be large enough to cover the misses seen within one TTL window, not the
+/// full key space — unlike the positive cache, it should stay small even
+/// under `CacheStrategy::Unbounded`.
+const DEFAULT_NEGATIVE_CAPACITY: usize = 1024;
+
+/// The outcome of a negative-caching-aware lookup via `Cache::lookup`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    /// The key maps to a confirmed database id.
+    Hit(i32),
+    /// The key was recently confirmed absent (within the negative TTL), so the
+    /// caller can skip a database read.
+    NegativeHit,
+    /// Neither a positive nor a (still-valid) negative entry exists.
+    Miss,
+}
+
+/// Selects the caching strategy backing a `Cache`.
+///
+/// Workloads differ in how much key reuse they see: a fixed key space fits
+/// entirely in memory and gains nothing from eviction, while a firehose of
+/// unique keys pays for LRU bookkeeping it never benefits from. `CacheStrategy`
+/// lets callers pick the backend that matches their workload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStrategy {
+    /// A fixed-capacity LRU cache that evicts the least recently used entry
+    /// once full.
+    Bounded(usize),
+    /// An unbounded cache backed by a plain `HashMap`. No entries are ever
+    /// evicted, so `evictions()` stays 0. Suited to workloads where the full
+    /// key space fits comfortably in memory.
+    Unbounded,
+    /// Caching is turned off entirely: `get` always misses and `put` is a
+    /// no-op. Useful for workloads with no key reuse, where the mutex
+    /// contention and allocation of a cache is pure overhead.
+    Disabled,
+}
+
+/// The storage backend selected by a `CacheStrategy`.
+enum Backend {
+    Bounded(Mutex<LruCache<String, i32>>),
+    Unbounded(Mutex<HashMap<String, i32>>),
+    Disabled,
+}
+
+/// Builds a negative-entry store with the given capacity (minimum 1).
+fn new_negative_store(capacity: usize) -> LruCache<String, Instant> {
+    LruCache::new(NonZeroUsize::new(capacity.max(1)).expect("capacity should be non-zero after max(1)"))
+}
 
-/// A thread-safe Least Recently Used (LRU) cache.
+/// A thread-safe cache mapping canonicalised JSON strings to database IDs.
 ///
-/// This struct wraps an `LruCache` in a `Mutex` to allow concurrent access
-/// from multiple threads. It maps canonicalised JSON strings to their
-/// corresponding database IDs. It also tracks hit and miss statistics.
+/// The backing storage is selected by a `CacheStrategy`: a fixed-capacity LRU
+/// cache, an unbounded `HashMap`, or disabled entirely. It also tracks hit and
+/// miss statistics regardless of strategy.
 pub struct Cache {
-    inner: Mutex<LruCache<String, i32>>,
+    backend: Backend,
     capacity: usize,
     hits: AtomicU64,
     misses: AtomicU64,
     evictions: AtomicU64,
+    negative_hits: AtomicU64,
+    /// `None` means negative caching is disabled (the default, opt-in via
+    /// `with_negative_caching`/`with_negative_ttl`).
+    negative_ttl: Option<Duration>,
+    /// A small, capacity-bounded set of recently-confirmed-absent keys.
+    /// Bounded (rather than a plain `HashMap`) so that a workload of many
+    /// distinct, never-repeated misses can't grow this set without limit;
+    /// entries also still expire lazily via `check_negative`/`DEFAULT_NEGATIVE_TTL`.
+    negative: Mutex<LruCache<String, Instant>>,
 }
 
 impl Cache {
-    /// Creates a new `Cache` with the specified capacity.
+    /// Creates a new bounded LRU `Cache` with the specified capacity.
     ///
     /// # Arguments
     ///
     /// * `capacity` - The maximum number of items the cache can hold. Minimum capacity is 1.
     pub fn new(capacity: usize) -> Self {
-        // Ensure capacity is at least 1 to avoid panic
-        let safe_capacity = capacity.max(1);
-        Self {
-            inner: Mutex::new(LruCache::new(
-                NonZeroUsize::new(safe_capacity).expect("capacity should be non-zero after max(1)"),
-            )),
-            capacity: safe_capacity,
-            hits: AtomicU64::new(0),
-            misses: AtomicU64::new(0),
-            evictions: AtomicU64::new(0),
+        Self::with_strategy(CacheStrategy::Bounded(capacity))
+    }
+
+    /// Creates a new `Cache` backed by the given `CacheStrategy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The caching strategy to use.
+    pub fn with_strategy(strategy: CacheStrategy) -> Self {
+        match strategy {
+            CacheStrategy::Bounded(capacity) => {
+                // Ensure capacity is at least 1 to avoid panic
+                let safe_capacity = capacity.max(1);
+                Self {
+                    backend: Backend::Bounded(Mutex::new(LruCache::new(
+                        NonZeroUsize::new(safe_capacity)
+                            .expect("capacity should be non-zero after max(1)"),
+                    ))),
+                    capacity: safe_capacity,
+                    hits: AtomicU64::new(0),
+                    misses: AtomicU64::new(0),
+                    evictions: AtomicU64::new(0),
+                    negative_hits: AtomicU64::new(0),
+                    negative_ttl: None,
+                    negative: Mutex::new(new_negative_store(DEFAULT_NEGATIVE_CAPACITY)),
+                }
+            }
+            CacheStrategy::Unbounded => Self {
+                backend: Backend::Unbounded(Mutex::new(HashMap::new())),
+                capacity: usize::MAX,
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+                evictions: AtomicU64::new(0),
+                negative_hits: AtomicU64::new(0),
+                negative_ttl: None,
+                negative: Mutex::new(new_negative_store(DEFAULT_NEGATIVE_CAPACITY)),
+            },
+            CacheStrategy::Disabled => Self {
+                backend: Backend::Disabled,
+                capacity: 0,
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+                evictions: AtomicU64::new(0),
+                negative_hits: AtomicU64::new(0),
+                negative_ttl: None,
+                negative: Mutex::new(new_negative_store(DEFAULT_NEGATIVE_CAPACITY)),
+            },
+        }
+    }
+
+    /// Enables negative caching with the default TTL (30s).
+    ///
+    /// Opt-in: by default `Cache` only ever stores confirmed key→id mappings.
+    /// Once enabled, `lookup` can return `Lookup::NegativeHit` for a key
+    /// recently marked absent via `put_negative`, letting callers skip a
+    /// database read within the TTL window.
+    #[must_use]
+    pub fn with_negative_caching(self) -> Self {
+        self.with_negative_ttl(DEFAULT_NEGATIVE_TTL)
+    }
+
+    /// Enables negative caching with a custom TTL.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - How long a `put_negative` entry is honoured before it
+    ///   expires and reverts to a true miss. Keep this short enough that a
+    ///   concurrently-inserted id isn't masked for long.
+    #[must_use]
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = Some(ttl);
+        self
+    }
+
+    /// Overrides the negative-entry set's capacity (default
+    /// `DEFAULT_NEGATIVE_CAPACITY`). Once full, `put_negative` evicts the
+    /// least-recently-used entry, same as the `Bounded` positive cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of negative entries to retain.
+    ///   Minimum capacity is 1.
+    #[must_use]
+    pub fn with_negative_capacity(self, capacity: usize) -> Self {
+        if let Ok(mut negative) = self.negative.lock() {
+            *negative = new_negative_store(capacity);
         }
+        self
     }
 
     /// Retrieves an ID from the cache if it exists.
@@ -46,10 +190,11 @@ impl Cache {
     ///
     /// `Some(i32)` if the key exists, `None` otherwise.
     /// Returns `None` if the cache mutex is poisoned (treated as cache miss).
+    ///
+    /// This does not consult the negative cache; use `lookup` to also
+    /// distinguish a recently-confirmed-absent key from a true miss.
     pub fn get(&self, key: &str) -> Option<i32> {
-        // Handle poisoned mutex gracefully by treating it as a cache miss
-        let mut cache = self.inner.lock().ok()?;
-        let result = cache.get(key).copied();
+        let result = self.raw_get(key);
 
         if result.is_some() {
             self.hits.fetch_add(1, Ordering::Relaxed);
@@ -60,6 +205,57 @@ impl Cache {
         result
     }
 
+    /// Looks up a key, distinguishing a positive hit, a negative hit (recently
+    /// confirmed absent, within the negative TTL), and a true miss.
+    ///
+    /// Negative hits only occur once negative caching has been enabled via
+    /// `with_negative_caching`/`with_negative_ttl`; otherwise this behaves
+    /// like `get`, except returning `Lookup::Miss` instead of `None`.
+    pub fn lookup(&self, key: &str) -> Lookup {
+        if let Some(id) = self.raw_get(key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Lookup::Hit(id);
+        }
+
+        if self.check_negative(key) {
+            self.negative_hits.fetch_add(1, Ordering::Relaxed);
+            return Lookup::NegativeHit;
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        Lookup::Miss
+    }
+
+    /// Backend lookup with no statistics side effects.
+    fn raw_get(&self, key: &str) -> Option<i32> {
+        match &self.backend {
+            Backend::Bounded(inner) => inner.lock().ok()?.get(key).copied(),
+            Backend::Unbounded(inner) => inner.lock().ok()?.get(key).copied(),
+            Backend::Disabled => None,
+        }
+    }
+
+    /// Returns `true` if `key` has a still-valid negative cache entry,
+    /// removing it first if it has expired.
+    fn check_negative(&self, key: &str) -> bool {
+        let Some(ttl) = self.negative_ttl else {
+            return false;
+        };
+
+        let Ok(mut negative) = self.negative.lock() else {
+            return false;
+        };
+
+        match negative.get(key) {
+            Some(marked_at) if marked_at.elapsed() < ttl => true,
+            Some(_) => {
+                negative.pop(key);
+                false
+            }
+            None => false,
+        }
+    }
+
     /// Inserts a key-value pair into the cache.
     ///
     /// # Arguments
@@ -68,14 +264,46 @@ impl Cache {
     /// * `value` - The database ID associated with the key.
     ///
     /// If the cache mutex is poisoned, the operation is silently skipped.
+    /// A no-op when the strategy is `Disabled`. Clears any negative cache
+    /// entry for `key`, since it is now confirmed present.
     pub fn put(&self, key: String, value: i32) {
-        // Handle poisoned mutex gracefully by skipping the cache update
-        if let Ok(mut cache) = self.inner.lock() {
-            // Track eviction if cache is at capacity and key doesn't exist
-            if cache.len() >= cache.cap().get() && !cache.contains(&key) {
-                self.evictions.fetch_add(1, Ordering::Relaxed);
+        match &self.backend {
+            Backend::Bounded(inner) => {
+                if let Ok(mut cache) = inner.lock() {
+                    // Track eviction if cache is at capacity and key doesn't exist
+                    if cache.len() >= cache.cap().get() && !cache.contains(&key) {
+                        self.evictions.fetch_add(1, Ordering::Relaxed);
+                    }
+                    cache.put(key.clone(), value);
+                }
+            }
+            Backend::Unbounded(inner) => {
+                if let Ok(mut cache) = inner.lock() {
+                    cache.insert(key.clone(), value);
+                }
+            }
+            Backend::Disabled => {}
+        }
+
+        if self.negative_ttl.is_some() {
+            if let Ok(mut negative) = self.negative.lock() {
+                negative.pop(&key);
             }
-            cache.put(key, value);
+        }
+    }
+
+    /// Records `key` as recently confirmed absent from the database.
+    ///
+    /// A no-op unless negative caching has been enabled via
+    /// `with_negative_caching`/`with_negative_ttl`. Evicts the
+    /// least-recently-used negative entry if the set is at capacity.
+    pub fn put_negative(&self, key: String) {
+        if self.negative_ttl.is_none() {
+            return;
+        }
+
+        if let Ok(mut negative) = self.negative.lock() {
+            negative.put(key, Instant::now());
         }
     }
 
@@ -97,8 +325,23 @@ impl Cache {
         self.misses.load(Ordering::Relaxed)
     }
 
+    /// Returns the number of negative cache hits (lookups recognised as
+    /// recently-confirmed-absent within the TTL window, without a database read).
+    ///
+    /// # Returns
+    ///
+    /// The total number of negative cache hits. Always 0 unless negative
+    /// caching has been enabled.
+    pub fn negative_hits(&self) -> u64 {
+        self.negative_hits.load(Ordering::Relaxed)
+    }
+
     /// Returns the cache hit rate as a percentage.
     ///
+    /// Counts negative hits alongside positive hits in the numerator, since
+    /// both avoid a database read; `hits()`/`misses()`/`negative_hits()` remain
+    /// available individually for a finer-grained breakdown.
+    ///
     /// # Returns
     ///
     /// The hit rate as a float between 0.0 and 100.0.
@@ -106,12 +349,13 @@ impl Cache {
     pub fn hit_rate(&self) -> f64 {
         let hits = self.hits();
         let misses = self.misses();
-        let total = hits + misses;
+        let negative_hits = self.negative_hits();
+        let total = hits + misses + negative_hits;
 
         if total == 0 {
             0.0
         } else {
-            (hits as f64 / total as f64) * 100.0
+            ((hits + negative_hits) as f64 / total as f64) * 100.0
         }
     }
 
@@ -122,14 +366,19 @@ impl Cache {
     /// The number of items currently stored in the cache.
     /// Returns 0 if the cache mutex is poisoned.
     pub fn size(&self) -> usize {
-        self.inner.lock().ok().map(|cache| cache.len()).unwrap_or(0)
+        match &self.backend {
+            Backend::Bounded(inner) => inner.lock().ok().map(|cache| cache.len()).unwrap_or(0),
+            Backend::Unbounded(inner) => inner.lock().ok().map(|cache| cache.len()).unwrap_or(0),
+            Backend::Disabled => 0,
+        }
     }
 
     /// Returns the maximum capacity of the cache.
     ///
     /// # Returns
     ///
-    /// The maximum number of items the cache can hold.
+    /// The maximum number of items the cache can hold. `usize::MAX` for
+    /// `CacheStrategy::Unbounded`, `0` for `CacheStrategy::Disabled`.
     pub fn capacity(&self) -> usize {
         self.capacity
     }
@@ -138,7 +387,8 @@ impl Cache {
     ///
     /// # Returns
     ///
-    /// The total number of items evicted from the cache.
+    /// The total number of items evicted from the cache. Always 0 for
+    /// `CacheStrategy::Unbounded` and `CacheStrategy::Disabled`.
     pub fn evictions(&self) -> u64 {
         self.evictions.load(Ordering::Relaxed)
     }
@@ -256,4 +506,105 @@ mod tests {
         assert_eq!(cache.size(), 2);
         assert_eq!(cache.evictions(), 2);
     }
+
+    #[test]
+    fn test_cache_unbounded_never_evicts() {
+        // Verifies that an unbounded cache never evicts entries.
+        let cache = Cache::with_strategy(CacheStrategy::Unbounded);
+        assert_eq!(cache.capacity(), usize::MAX);
+
+        for i in 0..1000 {
+            cache.put(format!("key{i}"), i);
+        }
+
+        assert_eq!(cache.size(), 1000);
+        assert_eq!(cache.evictions(), 0);
+        assert_eq!(cache.get("key0"), Some(0));
+        assert_eq!(cache.get("key999"), Some(999));
+    }
+
+    #[test]
+    fn test_cache_disabled_always_misses() {
+        // Verifies that a disabled cache never stores anything, but still
+        // counts misses so hit_rate stays meaningful.
+        let cache = Cache::with_strategy(CacheStrategy::Disabled);
+
+        cache.put("key1".to_string(), 1);
+        assert_eq!(cache.get("key1"), None);
+        assert_eq!(cache.size(), 0);
+        assert_eq!(cache.evictions(), 0);
+        assert_eq!(cache.hits(), 0);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn test_negative_caching_disabled_by_default() {
+        let cache = Cache::new(10);
+        cache.put_negative("missing".to_string());
+
+        assert_eq!(cache.lookup("missing"), Lookup::Miss);
+        assert_eq!(cache.negative_hits(), 0);
+    }
+
+    #[test]
+    fn test_negative_caching_hit_within_ttl() {
+        let cache = Cache::new(10).with_negative_ttl(Duration::from_secs(60));
+        cache.put_negative("missing".to_string());
+
+        assert_eq!(cache.lookup("missing"), Lookup::NegativeHit);
+        assert_eq!(cache.lookup("missing"), Lookup::NegativeHit);
+        assert_eq!(cache.negative_hits(), 2);
+        assert_eq!(cache.misses(), 0);
+    }
+
+    #[test]
+    fn test_negative_caching_expires_after_ttl() {
+        let cache = Cache::new(10).with_negative_ttl(Duration::from_millis(10));
+        cache.put_negative("missing".to_string());
+
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert_eq!(cache.lookup("missing"), Lookup::Miss);
+        assert_eq!(cache.negative_hits(), 0);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn test_put_clears_negative_entry() {
+        let cache = Cache::new(10).with_negative_ttl(Duration::from_secs(60));
+        cache.put_negative("key1".to_string());
+        assert_eq!(cache.lookup("key1"), Lookup::NegativeHit);
+
+        cache.put("key1".to_string(), 42);
+
+        assert_eq!(cache.lookup("key1"), Lookup::Hit(42));
+    }
+
+    #[test]
+    fn test_hit_rate_counts_negative_hits() {
+        let cache = Cache::new(10).with_negative_caching();
+        cache.put_negative("missing".to_string());
+
+        assert_eq!(cache.lookup("missing"), Lookup::NegativeHit);
+        assert_eq!(cache.lookup("other"), Lookup::Miss);
+
+        assert_eq!(cache.hit_rate(), 50.0);
+    }
+
+    #[test]
+    fn test_negative_entries_are_capacity_bounded() {
+        // Even with an Unbounded positive cache, a firehose of distinct,
+        // never-repeated misses must not grow the negative set without limit.
+        let cache = Cache::with_strategy(CacheStrategy::Unbounded)
+            .with_negative_ttl(Duration::from_secs(60))
+            .with_negative_capacity(2);
+
+        cache.put_negative("a".to_string());
+        cache.put_negative("b".to_string());
+        cache.put_negative("c".to_string()); // evicts "a" (least recently used)
+
+        assert_eq!(cache.lookup("a"), Lookup::Miss);
+        assert_eq!(cache.lookup("b"), Lookup::NegativeHit);
+        assert_eq!(cache.lookup("c"), Lookup::NegativeHit);
+    }
 }
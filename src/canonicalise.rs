@@ -1,4 +1,308 @@
 use serde_json::Value;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::errors::JsonRegisterError;
+
+/// Which profile [`canonicalise_with`] uses to serialize a `Value`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CanonicalForm {
+    /// The original behaviour: [`canonicalise`], i.e. `serde_json::to_string`
+    /// with Rust's own float formatting. Keys sort by byte order (via
+    /// `serde_json::Map`'s `BTreeMap`), which agrees with UTF-16 code unit
+    /// order for every key except ones containing astral-plane characters.
+    #[default]
+    Default,
+    /// [RFC 8785](https://www.rfc-editor.org/rfc/rfc8785) (JSON
+    /// Canonicalization Scheme): numbers are serialized per the ECMAScript
+    /// `Number::toString` algorithm and keys are sorted by UTF-16 code unit.
+    /// Use this when the canonical bytes must agree with a JCS-compliant
+    /// implementation in another language.
+    Jcs,
+    /// Mozilla-style CanonicalJSON: keys sorted by UTF-16 code unit, numbers
+    /// formatted the same way [`canonicalise`] formats them, but every code
+    /// point at or above `0x80` (in both keys and string values) is escaped
+    /// as one or two lowercase `\uXXXX` units instead of emitted as raw
+    /// UTF-8. Use this to interoperate with tools that expect an ASCII-only
+    /// canonical byte stream.
+    AsciiEscaped,
+}
+
+/// Canonicalises `json` under the given [`CanonicalForm`].
+///
+/// # Errors
+///
+/// Returns `JsonRegisterError::SerdeError` if serialization itself fails, or
+/// `JsonRegisterError::Canonicalisation` if `form` is `Jcs` and `json`
+/// contains a `NaN` or infinite number (JCS has no representation for
+/// either).
+pub fn canonicalise_with(json: &Value, form: CanonicalForm) -> Result<String, JsonRegisterError> {
+    match form {
+        CanonicalForm::Default => canonicalise(json).map_err(JsonRegisterError::SerdeError),
+        CanonicalForm::Jcs => jcs_value(json),
+        CanonicalForm::AsciiEscaped => ascii_value(json),
+    }
+}
+
+/// Recursively renders `value` per RFC 8785: object keys sorted by UTF-16
+/// code unit, numbers via [`jcs_number`], strings with the same minimal
+/// escaping `serde_json` already produces.
+fn jcs_value(value: &Value) -> Result<String, JsonRegisterError> {
+    match value {
+        Value::Null => Ok("null".to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Number(n) => jcs_number_value(n),
+        Value::String(s) => serde_json::to_string(s).map_err(JsonRegisterError::SerdeError),
+        Value::Array(items) => {
+            let parts = items.iter().map(jcs_value).collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("[{}]", parts.join(",")))
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by(|a, b| a.encode_utf16().cmp(b.encode_utf16()));
+
+            let parts = keys
+                .into_iter()
+                .map(|key| {
+                    let encoded_key =
+                        serde_json::to_string(key).map_err(JsonRegisterError::SerdeError)?;
+                    let encoded_value = jcs_value(&map[key])?;
+                    Ok::<_, JsonRegisterError>(format!("{encoded_key}:{encoded_value}"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("{{{}}}", parts.join(",")))
+        }
+    }
+}
+
+/// Formats a `serde_json::Number` per JCS. Integers that `serde_json` stores
+/// as exact `i64`/`u64` are emitted directly; everything else goes through
+/// [`jcs_number`]'s `f64` algorithm.
+fn jcs_number_value(n: &serde_json::Number) -> Result<String, JsonRegisterError> {
+    if let Some(i) = n.as_i64() {
+        return Ok(i.to_string());
+    }
+    if let Some(u) = n.as_u64() {
+        return Ok(u.to_string());
+    }
+    let f = n.as_f64().ok_or_else(|| {
+        JsonRegisterError::Canonicalisation(format!("number {n} has no f64 representation"))
+    })?;
+    jcs_number(f)
+}
+
+/// Formats `value` per the ECMAScript `Number::toString` algorithm: the
+/// shortest decimal digit string that round-trips to `value`, placed in
+/// plain decimal notation for magnitudes in `[1e-6, 1e21)` and in
+/// exponential notation (lowercase `e`, always signed) outside it. Integral
+/// values never get a trailing `.0`.
+///
+/// # Errors
+///
+/// Returns `JsonRegisterError::Canonicalisation` if `value` is `NaN` or
+/// infinite, neither of which JCS can represent.
+fn jcs_number(value: f64) -> Result<String, JsonRegisterError> {
+    if value.is_nan() || value.is_infinite() {
+        return Err(JsonRegisterError::Canonicalisation(
+            "JCS numbers cannot be NaN or Infinity".to_string(),
+        ));
+    }
+    // ECMAScript's ToString(-0) is "0", same as ToString(0).
+    if value == 0.0 {
+        return Ok("0".to_string());
+    }
+
+    let negative = value.is_sign_negative();
+    let abs = value.abs();
+
+    // Rust's `{:e}` already produces the shortest round-tripping mantissa,
+    // normalised to a single leading nonzero digit — exactly the digit
+    // string ECMAScript's algorithm needs; only the placement (plain vs
+    // exponential) differs, which we apply below.
+    let scientific = format!("{abs:e}");
+    let (mantissa, exp_str) = scientific
+        .split_once('e')
+        .expect("Rust's {:e} formatting always includes an exponent");
+    let exponent: i32 = exp_str
+        .parse()
+        .expect("Rust's {:e} exponent is always a valid integer");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let digit_count = digits.len() as i32;
+
+    let body = if !(-6..21).contains(&exponent) {
+        let (first, rest) = digits.split_at(1);
+        let mantissa = if rest.is_empty() {
+            first.to_string()
+        } else {
+            format!("{first}.{rest}")
+        };
+        let sign = if exponent >= 0 { "+" } else { "-" };
+        format!("{mantissa}e{sign}{}", exponent.abs())
+    } else if exponent >= 0 {
+        let int_len = (exponent + 1) as usize;
+        if digit_count <= exponent + 1 {
+            format!("{digits}{}", "0".repeat(int_len - digit_count as usize))
+        } else {
+            let (int_part, frac_part) = digits.split_at(int_len);
+            format!("{int_part}.{frac_part}")
+        }
+    } else {
+        let zeros = (-exponent - 1) as usize;
+        format!("0.{}{digits}", "0".repeat(zeros))
+    };
+
+    Ok(if negative { format!("-{body}") } else { body })
+}
+
+/// The largest (and, negated, the smallest) integer a JSON number can hold
+/// while still round-tripping through an IEEE 754 double without loss,
+/// i.e. ECMAScript's `Number.MAX_SAFE_INTEGER`. [`canonicalise_strict`]
+/// rejects any integer outside `±MAX_SAFE_INTEGER` so that documents it
+/// accepts are portable to implementations that decode numbers as `f64`.
+const MAX_SAFE_INTEGER: u64 = 9_007_199_254_740_991;
+
+/// Canonicalises `json` the same way [`canonicalise`] does, but rejects any
+/// floating-point number, any integer outside `±`[`MAX_SAFE_INTEGER`], and
+/// (trivially, since `serde_json::Number` cannot hold either) any `NaN` or
+/// `Infinity`. Use this before signing or registering a document to
+/// guarantee it won't be reinterpreted differently — e.g. rounded to
+/// `3.1400000000000001` — by another implementation.
+///
+/// # Errors
+///
+/// Returns `JsonRegisterError::Canonicalisation` naming the offending
+/// value's JSON pointer (e.g. `/users/0/age`) as soon as one is found.
+pub fn canonicalise_strict(json: &Value) -> Result<String, JsonRegisterError> {
+    strict_value(json, "")
+}
+
+/// Recursive worker for [`canonicalise_strict`]. `path` is the JSON pointer
+/// to `value` accumulated so far, used only to identify the offending value
+/// in an error.
+fn strict_value(value: &Value, path: &str) -> Result<String, JsonRegisterError> {
+    match value {
+        Value::Null => Ok("null".to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Number(n) => strict_number(n, path),
+        Value::String(s) => serde_json::to_string(s).map_err(JsonRegisterError::SerdeError),
+        Value::Array(items) => {
+            let parts = items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| strict_value(item, &format!("{path}/{index}")))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("[{}]", parts.join(",")))
+        }
+        Value::Object(map) => {
+            let parts = map
+                .iter()
+                .map(|(key, item)| {
+                    let encoded_key =
+                        serde_json::to_string(key).map_err(JsonRegisterError::SerdeError)?;
+                    let encoded_value = strict_value(item, &format!("{path}/{key}"))?;
+                    Ok::<_, JsonRegisterError>(format!("{encoded_key}:{encoded_value}"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("{{{}}}", parts.join(",")))
+        }
+    }
+}
+
+/// Accepts only `i64`/`u64`-representable integers within the safe range;
+/// everything else (a float, however whole-valued, or an out-of-range
+/// integer) is an error naming `path`.
+fn strict_number(n: &serde_json::Number, path: &str) -> Result<String, JsonRegisterError> {
+    if let Some(i) = n.as_i64() {
+        if i.unsigned_abs() > MAX_SAFE_INTEGER {
+            return Err(JsonRegisterError::Canonicalisation(format!(
+                "{path}: integer {i} is outside the safe range (+/-{MAX_SAFE_INTEGER})"
+            )));
+        }
+        return Ok(i.to_string());
+    }
+    if let Some(u) = n.as_u64() {
+        if u > MAX_SAFE_INTEGER {
+            return Err(JsonRegisterError::Canonicalisation(format!(
+                "{path}: integer {u} is outside the safe range (+/-{MAX_SAFE_INTEGER})"
+            )));
+        }
+        return Ok(u.to_string());
+    }
+    Err(JsonRegisterError::Canonicalisation(format!(
+        "{path}: floating-point numbers are not allowed in strict canonical form (found {n})"
+    )))
+}
+
+/// Recursively renders `value` with keys sorted by UTF-16 code unit (same
+/// order as [`jcs_value`]) and every non-ASCII code point, in both keys and
+/// string values, escaped as `\uXXXX` rather than emitted as raw UTF-8.
+/// Numbers are formatted exactly as [`canonicalise`] formats them.
+fn ascii_value(value: &Value) -> Result<String, JsonRegisterError> {
+    match value {
+        Value::Null => Ok("null".to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Number(n) => serde_json::to_string(n).map_err(JsonRegisterError::SerdeError),
+        Value::String(s) => Ok(ascii_escape_string(s)),
+        Value::Array(items) => {
+            let parts = items
+                .iter()
+                .map(ascii_value)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("[{}]", parts.join(",")))
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by(|a, b| a.encode_utf16().cmp(b.encode_utf16()));
+
+            let parts = keys
+                .into_iter()
+                .map(|key| {
+                    let encoded_value = ascii_value(&map[key])?;
+                    Ok::<_, JsonRegisterError>(format!(
+                        "{}:{encoded_value}",
+                        ascii_escape_string(key)
+                    ))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("{{{}}}", parts.join(",")))
+        }
+    }
+}
+
+/// Renders `s` as a quoted JSON string literal using only ASCII bytes: the
+/// usual short escapes (`\"`, `\\`, `\n`, `\t`, `\r`, `\b`, `\f`) where JSON
+/// defines one, `\u00XX` for the remaining control characters, and `\uXXXX`
+/// (a surrogate pair for code points at or above `U+10000`) for every code
+/// point at or above `0x80`.
+fn ascii_escape_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c if (c as u32) < 0x80 => out.push(c),
+            c => {
+                let code_point = c as u32;
+                if code_point <= 0xffff {
+                    out.push_str(&format!("\\u{code_point:04x}"));
+                } else {
+                    let offset = code_point - 0x10000;
+                    let high_surrogate = 0xd800 + (offset >> 10);
+                    let low_surrogate = 0xdc00 + (offset & 0x3ff);
+                    out.push_str(&format!("\\u{high_surrogate:04x}\\u{low_surrogate:04x}"));
+                }
+            }
+        }
+    }
+    out.push('"');
+    out
+}
 
 /// Converts a JSON object to its canonical string representation.
 ///
@@ -21,6 +325,101 @@ pub fn canonicalise(json: &Value) -> Result<String, serde_json::Error> {
     serde_json::to_string(json)
 }
 
+/// Computes a hex-encoded SHA-256 digest of an already-canonicalised JSON string.
+///
+/// This is what `Db`'s hash-column dedup mode stores and indexes instead of
+/// the full JSONB document: two inputs that canonicalise to the same string
+/// (for example, objects differing only in original key order) always hash
+/// to the same digest.
+///
+/// # Arguments
+///
+/// * `canonical` - The output of [`canonicalise`].
+///
+/// # Returns
+///
+/// A 64-character lowercase hex string.
+pub fn digest_sha256(canonical: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(canonical.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Which hash [`canonicalise_digest`] computes over the canonical bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DigestAlgorithm {
+    /// What [`digest_sha256`] already computes; the default.
+    #[default]
+    Sha256,
+    /// For callers that need a larger digest than SHA-256 provides.
+    Sha512,
+    /// Faster than either SHA variant; use when interoperating with another
+    /// system's content-addressing isn't required.
+    Blake3,
+}
+
+/// Canonicalises `json` under `form` and hashes the resulting UTF-8 bytes
+/// with `algorithm`, returning `(canonical, digest)`. This is the
+/// `sha256(cjson(value))` key-id construction TUF uses, generalised to any
+/// canonical profile and hash choice this crate supports. Two values that
+/// canonicalise to the same string under `form` always get the same digest.
+///
+/// # Errors
+///
+/// Returns whatever [`canonicalise_with`] returns for `form`.
+pub fn canonicalise_digest(
+    json: &Value,
+    form: CanonicalForm,
+    algorithm: DigestAlgorithm,
+) -> Result<(String, String), JsonRegisterError> {
+    let canonical = canonicalise_with(json, form)?;
+    let digest = digest_hex(canonical.as_bytes(), algorithm);
+    Ok((canonical, digest))
+}
+
+/// Convenience around [`canonicalise_digest`] for callers that only need the
+/// content address, not the canonical string itself.
+///
+/// # Errors
+///
+/// Returns whatever [`canonicalise_with`] returns for `form`.
+pub fn digest_only(
+    json: &Value,
+    form: CanonicalForm,
+    algorithm: DigestAlgorithm,
+) -> Result<String, JsonRegisterError> {
+    canonicalise_digest(json, form, algorithm).map(|(_, digest)| digest)
+}
+
+/// Hex-encodes the hash of `bytes` under `algorithm`.
+fn digest_hex(bytes: &[u8], algorithm: DigestAlgorithm) -> String {
+    match algorithm {
+        DigestAlgorithm::Sha256 => {
+            let mut hasher = Sha256::new();
+            hasher.update(bytes);
+            hasher
+                .finalize()
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect()
+        }
+        DigestAlgorithm::Sha512 => {
+            let mut hasher = Sha512::new();
+            hasher.update(bytes);
+            hasher
+                .finalize()
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect()
+        }
+        DigestAlgorithm::Blake3 => blake3::hash(bytes).to_hex().to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,4 +571,259 @@ mod tests {
         let expected = r#"{"a_first":[3,2,1],"z_last":[{"a":1,"b":2},{"c":3,"d":4}]}"#;
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_digest_sha256_known_vector() {
+        // SHA-256 of the empty string is a well-known test vector.
+        assert_eq!(
+            digest_sha256(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_digest_sha256_deterministic_across_key_order() {
+        // Verifies the dedup invariant: objects differing only in key order
+        // canonicalise to the same string and therefore hash identically.
+        let obj1 = json!({"name": "Alice", "age": 30});
+        let obj2 = json!({"age": 30, "name": "Alice"});
+
+        let digest1 = digest_sha256(&canonicalise(&obj1).unwrap());
+        let digest2 = digest_sha256(&canonicalise(&obj2).unwrap());
+
+        assert_eq!(digest1, digest2);
+        assert_eq!(digest1.len(), 64);
+    }
+
+    #[test]
+    fn test_digest_sha256_differs_for_different_input() {
+        assert_ne!(digest_sha256("a"), digest_sha256("b"));
+    }
+
+    #[test]
+    fn test_jcs_integers() {
+        assert_eq!(canonicalise_with(&json!(100), CanonicalForm::Jcs).unwrap(), "100");
+        assert_eq!(canonicalise_with(&json!(0), CanonicalForm::Jcs).unwrap(), "0");
+        assert_eq!(canonicalise_with(&json!(-10), CanonicalForm::Jcs).unwrap(), "-10");
+    }
+
+    #[test]
+    fn test_jcs_drops_trailing_point_zero_for_integral_floats() {
+        assert_eq!(canonicalise_with(&json!(100.0), CanonicalForm::Jcs).unwrap(), "100");
+        assert_eq!(canonicalise_with(&json!(0.0), CanonicalForm::Jcs).unwrap(), "0");
+        assert_eq!(canonicalise_with(&json!(-0.0), CanonicalForm::Jcs).unwrap(), "0");
+    }
+
+    #[test]
+    fn test_jcs_large_exponent_emits_plain_decimal() {
+        // Unlike the default profile's "10000000000.0", JCS has no decimal
+        // point on an integral value, and stays in plain notation below 1e21.
+        assert_eq!(canonicalise_with(&json!(1e10), CanonicalForm::Jcs).unwrap(), "10000000000");
+    }
+
+    #[test]
+    fn test_jcs_exponent_boundary_switches_to_scientific() {
+        // The plain/exponential boundary is magnitude in `[1e-6, 1e21)`: 1e-6
+        // itself is still plain ("0.000001"), and 1e21 is the first value
+        // pushed into exponential notation on the upper end.
+        assert_eq!(canonicalise_with(&json!(1e21), CanonicalForm::Jcs).unwrap(), "1e+21");
+        assert_eq!(canonicalise_with(&json!(1e-7), CanonicalForm::Jcs).unwrap(), "1e-7");
+        assert_eq!(canonicalise_with(&json!(1e-6), CanonicalForm::Jcs).unwrap(), "0.000001");
+    }
+
+    #[test]
+    fn test_jcs_fractional_values() {
+        assert_eq!(canonicalise_with(&json!(3.14), CanonicalForm::Jcs).unwrap(), "3.14");
+        assert_eq!(canonicalise_with(&json!(0.1), CanonicalForm::Jcs).unwrap(), "0.1");
+        assert_eq!(canonicalise_with(&json!(-2.5), CanonicalForm::Jcs).unwrap(), "-2.5");
+    }
+
+    #[test]
+    fn test_jcs_rejects_nan_and_infinity() {
+        // `serde_json::Number` itself refuses to hold a NaN/Infinity, so the
+        // error path is exercised directly against `jcs_number`.
+        assert!(jcs_number(f64::NAN).is_err());
+        assert!(jcs_number(f64::INFINITY).is_err());
+        assert!(jcs_number(f64::NEG_INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_jcs_sorts_keys_by_utf16_code_unit() {
+        // U+10000 (an astral-plane character) sorts *before* U+E000 (a BMP
+        // private-use character) in UTF-16 code unit order, because
+        // U+10000's leading surrogate (0xD800) is less than 0xE000 — even
+        // though U+10000's own code point is numerically larger.
+        let obj = json!({"\u{10000}": 1, "\u{e000}": 2});
+        let result = canonicalise_with(&obj, CanonicalForm::Jcs).unwrap();
+        assert!(result.find('\u{10000}').unwrap() < result.find('\u{e000}').unwrap());
+    }
+
+    #[test]
+    fn test_jcs_matches_default_for_simple_object() {
+        let obj = json!({"b": 2, "a": 1});
+        assert_eq!(
+            canonicalise_with(&obj, CanonicalForm::Jcs).unwrap(),
+            r#"{"a":1,"b":2}"#
+        );
+    }
+
+    #[test]
+    fn test_jcs_nested_arrays_and_objects() {
+        let obj = json!({"z": [1, {"b": 2.0, "a": 1e10}], "a": null});
+        let result = canonicalise_with(&obj, CanonicalForm::Jcs).unwrap();
+        assert_eq!(result, r#"{"a":null,"z":[1,{"a":10000000000,"b":2}]}"#);
+    }
+
+    #[test]
+    fn test_ascii_escapes_non_ascii_keys_and_values() {
+        let obj = json!({"é": "✓"});
+        let result = canonicalise_with(&obj, CanonicalForm::AsciiEscaped).unwrap();
+        assert_eq!(result, r#"{"\u00e9":"\u2713"}"#);
+    }
+
+    #[test]
+    fn test_ascii_escapes_astral_code_point_as_surrogate_pair() {
+        let obj = json!({"emoji": "\u{1f600}"});
+        let result = canonicalise_with(&obj, CanonicalForm::AsciiEscaped).unwrap();
+        assert_eq!(result, r#"{"emoji":"\ud83d\ude00"}"#);
+    }
+
+    #[test]
+    fn test_ascii_uses_short_escapes_for_defined_control_characters() {
+        let obj = json!({"s": "line1\nline2\ttab\"quote\\back"});
+        let result = canonicalise_with(&obj, CanonicalForm::AsciiEscaped).unwrap();
+        assert_eq!(result, r#"{"s":"line1\nline2\ttab\"quote\\back"}"#);
+    }
+
+    #[test]
+    fn test_ascii_escapes_other_control_characters_as_u00xx() {
+        let obj = json!({"s": "\u{0001}"});
+        let result = canonicalise_with(&obj, CanonicalForm::AsciiEscaped).unwrap();
+        assert_eq!(result, r#"{"s":"\u0001"}"#);
+    }
+
+    #[test]
+    fn test_ascii_leaves_plain_ascii_unescaped() {
+        let obj = json!({"s": "hello world 123"});
+        let result = canonicalise_with(&obj, CanonicalForm::AsciiEscaped).unwrap();
+        assert_eq!(result, r#"{"s":"hello world 123"}"#);
+    }
+
+    #[test]
+    fn test_ascii_sorts_keys_by_utf16_code_unit() {
+        let obj = json!({"\u{10000}": 1, "\u{e000}": 2});
+        let result = canonicalise_with(&obj, CanonicalForm::AsciiEscaped).unwrap();
+        assert_eq!(result, r#"{"\ud800\udc00":1,"\ue000":2}"#);
+    }
+
+    #[test]
+    fn test_ascii_output_is_pure_ascii_bytes() {
+        let obj = json!({"русский": "日本語", "emoji": "🎉"});
+        let result = canonicalise_with(&obj, CanonicalForm::AsciiEscaped).unwrap();
+        assert!(result.is_ascii());
+    }
+
+    #[test]
+    fn test_strict_accepts_integers_and_strings() {
+        let obj = json!({"age": 30, "name": "Alice"});
+        assert_eq!(
+            canonicalise_strict(&obj).unwrap(),
+            r#"{"age":30,"name":"Alice"}"#
+        );
+    }
+
+    #[test]
+    fn test_strict_rejects_floating_point_value_with_path() {
+        let obj = json!({"users": [{"age": 30.5}]});
+        let err = canonicalise_strict(&obj).unwrap_err();
+        assert!(err.to_string().contains("/users/0/age"));
+    }
+
+    #[test]
+    fn test_strict_rejects_whole_valued_float() {
+        // 30.0 is stored as an f64, not an i64/u64, so it's rejected even
+        // though its value happens to be an integer.
+        let obj = json!({"age": 30.0});
+        assert!(canonicalise_strict(&obj).is_err());
+    }
+
+    #[test]
+    fn test_strict_rejects_integer_outside_safe_range() {
+        let obj = json!({"id": 9_007_199_254_740_992u64});
+        let err = canonicalise_strict(&obj).unwrap_err();
+        assert!(err.to_string().contains("/id"));
+    }
+
+    #[test]
+    fn test_strict_accepts_integer_at_safe_range_boundary() {
+        let obj = json!({"id": 9_007_199_254_740_991u64});
+        assert_eq!(canonicalise_strict(&obj).unwrap(), r#"{"id":9007199254740991}"#);
+    }
+
+    #[test]
+    fn test_strict_rejects_negative_integer_outside_safe_range() {
+        let obj = json!({"id": -9_007_199_254_740_992i64});
+        assert!(canonicalise_strict(&obj).is_err());
+    }
+
+    #[test]
+    fn test_strict_rejects_top_level_float() {
+        let obj = json!(1.5);
+        let err = canonicalise_strict(&obj).unwrap_err();
+        assert!(err.to_string().contains("floating-point"));
+    }
+
+    #[test]
+    fn test_canonicalise_digest_sha256_matches_digest_sha256() {
+        let obj = json!({"name": "Alice", "age": 30});
+        let (canonical, digest) =
+            canonicalise_digest(&obj, CanonicalForm::Default, DigestAlgorithm::Sha256).unwrap();
+        assert_eq!(canonical, canonicalise(&obj).unwrap());
+        assert_eq!(digest, digest_sha256(&canonical));
+    }
+
+    #[test]
+    fn test_canonicalise_digest_deterministic_across_key_order() {
+        let obj1 = json!({"name": "Alice", "age": 30});
+        let obj2 = json!({"age": 30, "name": "Alice"});
+        let (_, digest1) =
+            canonicalise_digest(&obj1, CanonicalForm::Jcs, DigestAlgorithm::Sha256).unwrap();
+        let (_, digest2) =
+            canonicalise_digest(&obj2, CanonicalForm::Jcs, DigestAlgorithm::Sha256).unwrap();
+        assert_eq!(digest1, digest2);
+    }
+
+    #[test]
+    fn test_canonicalise_digest_sha512_is_128_hex_chars() {
+        let obj = json!({"a": 1});
+        let (_, digest) =
+            canonicalise_digest(&obj, CanonicalForm::Default, DigestAlgorithm::Sha512).unwrap();
+        assert_eq!(digest.len(), 128);
+    }
+
+    #[test]
+    fn test_canonicalise_digest_blake3_is_64_hex_chars() {
+        let obj = json!({"a": 1});
+        let (_, digest) =
+            canonicalise_digest(&obj, CanonicalForm::Default, DigestAlgorithm::Blake3).unwrap();
+        assert_eq!(digest.len(), 64);
+    }
+
+    #[test]
+    fn test_canonicalise_digest_uses_selected_profile() {
+        let obj = json!({"é": 1});
+        let (canonical, _) =
+            canonicalise_digest(&obj, CanonicalForm::AsciiEscaped, DigestAlgorithm::Sha256)
+                .unwrap();
+        assert_eq!(canonical, r#"{"\u00e9":1}"#);
+    }
+
+    #[test]
+    fn test_digest_only_matches_canonicalise_digest() {
+        let obj = json!({"b": 2, "a": 1});
+        let (_, expected) =
+            canonicalise_digest(&obj, CanonicalForm::Default, DigestAlgorithm::Sha256).unwrap();
+        let digest = digest_only(&obj, CanonicalForm::Default, DigestAlgorithm::Sha256).unwrap();
+        assert_eq!(digest, expected);
+    }
 }
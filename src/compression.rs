@@ -0,0 +1,90 @@
+use crate::errors::JsonRegisterError;
+
+/// zstd's supported compression level range.
+const MIN_LEVEL: i32 = 1;
+const MAX_LEVEL: i32 = 22;
+
+/// Configures transparent zstd compression of the stored payload column.
+///
+/// Unlike [`crate::encryption::EncryptionConfig`], compression is
+/// deterministic — the same canonical string always compresses to the same
+/// bytes — so it doesn't force `DedupMode::HashColumn` the way encryption
+/// does: `DedupMode::JsonbUnique`'s `UNIQUE` constraint still dedups
+/// correctly, just over compressed bytes instead of raw JSONB. Either way
+/// the dedup key (the payload column itself, or the hash column) tracks the
+/// canonical plaintext, so semantics are unaffected by enabling compression.
+#[derive(Debug, Clone)]
+pub struct CompressionConfig {
+    level: i32,
+}
+
+impl CompressionConfig {
+    /// Creates a new compression configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The zstd compression level, from 1 (fastest) to 22
+    ///   (smallest). Higher levels trade CPU time for a smaller payload.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new config, or a `JsonRegisterError` if
+    /// `level` is out of zstd's supported range.
+    pub fn new(level: i32) -> Result<Self, JsonRegisterError> {
+        if !(MIN_LEVEL..=MAX_LEVEL).contains(&level) {
+            return Err(JsonRegisterError::Configuration(format!(
+                "compression level must be between {MIN_LEVEL} and {MAX_LEVEL}, got {level}"
+            )));
+        }
+        Ok(Self { level })
+    }
+
+    /// Compresses `plaintext` at the configured level.
+    pub(crate) fn compress(&self, plaintext: &[u8]) -> Vec<u8> {
+        zstd::encode_all(plaintext, self.level)
+            .expect("zstd compression of an in-memory buffer cannot fail")
+    }
+
+    /// Decompresses a buffer produced by [`Self::compress`].
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the original plaintext, or a
+    /// `JsonRegisterError` if `compressed` is truncated or not valid zstd
+    /// data.
+    pub(crate) fn decompress(&self, compressed: &[u8]) -> Result<Vec<u8>, JsonRegisterError> {
+        zstd::decode_all(compressed).map_err(|e| {
+            JsonRegisterError::RuntimeError(format!("zstd decompression failed: {e}"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_rejects_level_below_minimum() {
+        assert!(CompressionConfig::new(0).is_err());
+    }
+
+    #[test]
+    fn test_new_rejects_level_above_maximum() {
+        assert!(CompressionConfig::new(23).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let config = CompressionConfig::new(3).unwrap();
+        let plaintext = br#"{"name":"Alice"}"#.repeat(50);
+        let compressed = config.compress(&plaintext);
+        assert!(compressed.len() < plaintext.len());
+        assert_eq!(config.decompress(&compressed).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_decompress_rejects_garbage() {
+        let config = CompressionConfig::new(3).unwrap();
+        assert!(config.decompress(b"not zstd data").is_err());
+    }
+}
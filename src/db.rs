@@ -4,6 +4,202 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 use tokio_postgres::NoTls;
 
+use crate::compression::CompressionConfig;
+use crate::encryption::EncryptionConfig;
+
+/// A client certificate and private key, both PEM-encoded, used to authenticate
+/// to the database when the server requires mutual TLS.
+#[derive(Debug, Clone)]
+pub struct ClientIdentity {
+    /// PEM-encoded client certificate.
+    pub cert_pem: Vec<u8>,
+    /// PEM-encoded private key for the client certificate.
+    pub key_pem: Vec<u8>,
+}
+
+/// Configures how `Db` connects to PostgreSQL with respect to TLS.
+///
+/// This mirrors the `sslmode`/connector choice made by real `tokio-postgres`
+/// deployments: connect in the clear, upgrade using the platform's trusted
+/// root certificates, or pin a specific CA (optionally presenting a client
+/// identity for mutual TLS).
+///
+/// The connector underneath (see `build_tls_connector`) is built on
+/// `native-tls`/`postgres-native-tls` rather than `rustls`: it lets the CA
+/// and client identity stay PEM bytes decoded once here instead of also
+/// threading a second certificate-parsing path through `ConnectionConfig`,
+/// and it picks up the platform trust store (`PlatformRootStore`) for free.
+#[derive(Debug, Clone, Default)]
+pub enum TlsConfig {
+    /// Connect without TLS. Equivalent to `sslmode=disable`.
+    #[default]
+    Disabled,
+    /// Connect with TLS, trusting the platform's root certificate store.
+    PlatformRootStore,
+    /// Connect with TLS, trusting only a user-supplied CA certificate
+    /// (PEM-encoded) and optionally presenting a client identity.
+    CustomCa {
+        /// PEM-encoded CA certificate used to verify the server.
+        ca_cert_pem: Vec<u8>,
+        /// Optional client certificate/key pair for mutual TLS.
+        client_identity: Option<ClientIdentity>,
+    },
+}
+
+#[cfg(feature = "tls")]
+fn build_tls_connector(
+    tls: &TlsConfig,
+) -> Result<postgres_native_tls::MakeTlsConnector, crate::errors::JsonRegisterError> {
+    use native_tls::{Certificate, Identity, TlsConnector};
+
+    let mut builder = TlsConnector::builder();
+
+    if let TlsConfig::CustomCa {
+        ca_cert_pem,
+        client_identity,
+    } = tls
+    {
+        let ca_cert = Certificate::from_pem(ca_cert_pem).map_err(|e| {
+            crate::errors::JsonRegisterError::Configuration(format!(
+                "invalid CA certificate: {}",
+                e
+            ))
+        })?;
+        builder.add_root_certificate(ca_cert);
+
+        if let Some(identity) = client_identity {
+            let pkcs8 = Identity::from_pkcs8(&identity.cert_pem, &identity.key_pem)
+                .map_err(|e| {
+                    crate::errors::JsonRegisterError::Configuration(format!(
+                        "invalid client identity: {}",
+                        e
+                    ))
+                })?;
+            builder.identity(pkcs8);
+        }
+    }
+
+    let connector = builder.build().map_err(|e| {
+        crate::errors::JsonRegisterError::Configuration(format!(
+            "failed to build TLS connector: {}",
+            e
+        ))
+    })?;
+
+    Ok(postgres_native_tls::MakeTlsConnector::new(connector))
+}
+
+/// Controls how `Db` deduplicates objects on insert.
+///
+/// The default, `JsonbUnique`, relies on a `UNIQUE` constraint over the whole
+/// `jsonb_column`, which forces Postgres to maintain a unique index over
+/// entire documents. `HashColumn` instead dedups on a fixed-width digest
+/// (see [`crate::canonicalise::digest_sha256`]) stored in its own
+/// unique-indexed column, giving a constant-size index regardless of
+/// document size. Because the digest is fixed-width, `Db` treats a hash
+/// match as provisional and compares the stored payload against the
+/// submitted one before trusting it (see `Db::verify_hash_match`), falling
+/// back correctly in the astronomically unlikely event of a collision.
+#[derive(Debug, Clone, Default)]
+pub enum DedupMode {
+    /// Dedup via a `UNIQUE` constraint on `jsonb_column`. Matches the
+    /// long-standing schema and requires no migration.
+    #[default]
+    JsonbUnique,
+    /// Dedup via a `UNIQUE` constraint on a separate hash column, whose name
+    /// is given here. The column must already exist in the target table.
+    HashColumn {
+        /// The name of the column storing the SHA-256 digest.
+        hash_column: String,
+    },
+}
+
+/// Controls whether `Db` prepares and caches the statements it issues, or
+/// prepares them afresh on every call without caching them.
+///
+/// This is the same knob diesel exposes as `set_prepared_statement_cache_size`.
+/// Caching a prepared statement avoids Postgres re-parsing/re-planning the
+/// same SQL text on every round-trip, but named prepared statements don't
+/// survive across transactions under PgBouncer-style transaction pooling, so
+/// those deployments need caching disabled entirely.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatementCacheMode {
+    /// Prepare each statement once per pooled connection and reuse it for
+    /// the connection's lifetime. The default, matching `Db`'s long-standing
+    /// behaviour.
+    #[default]
+    Unbounded,
+    /// Prepare each statement afresh on every call instead of caching it, so
+    /// no prepared statement is reused by a later call. The statement is
+    /// still prepared on the server, in a round-trip of its own before it is
+    /// executed; statements are never sent unprepared.
+    Disabled,
+}
+
+/// Bounds on how many objects `Db::register_batch_objects` sends to Postgres
+/// in a single `$1::jsonb[]` parameter.
+///
+/// Oversized batches risk exceeding practical query/parameter size limits and
+/// produce unbounded memory spikes server-side, so `register_batch_objects`
+/// splits its input into contiguous chunks no larger than either limit.
+///
+/// This is not a workaround for Postgres' 65,535-bind-parameter-per-query
+/// limit: `register_batch_query` always binds exactly one or two `$n`
+/// placeholders (the hash column's values and the payload's, in
+/// `DedupMode::HashColumn`) and passes the whole batch as a single array
+/// parameter via `unnest`, so that limit doesn't scale with batch size at
+/// all. `max_elements`/`max_bytes` exist purely to bound per-request memory
+/// and query size, not parameter count.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchLimits {
+    /// Maximum number of elements per chunk.
+    pub max_elements: usize,
+    /// Maximum aggregate byte size (sum of UTF-8 lengths) per chunk.
+    pub max_bytes: usize,
+}
+
+impl Default for BatchLimits {
+    /// Defaults to 1,000 elements or 200 KB aggregate payload, whichever is hit first.
+    fn default() -> Self {
+        Self {
+            max_elements: 1_000,
+            max_bytes: 200 * 1024,
+        }
+    }
+}
+
+/// Splits `json_strs` into contiguous chunks, each no larger than `limits`
+/// allows by element count or aggregate byte size. A single element that
+/// itself exceeds `max_bytes` still gets its own (oversized) chunk rather than
+/// being dropped or split mid-value.
+fn chunk_batch<'a>(json_strs: &'a [String], limits: &BatchLimits) -> Vec<&'a [String]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut count = 0;
+    let mut bytes = 0;
+
+    for (i, s) in json_strs.iter().enumerate() {
+        let would_exceed_count = count + 1 > limits.max_elements;
+        let would_exceed_bytes = count > 0 && bytes + s.len() > limits.max_bytes;
+
+        if would_exceed_count || would_exceed_bytes {
+            chunks.push(&json_strs[start..i]);
+            start = i;
+            count = 0;
+            bytes = 0;
+        }
+
+        count += 1;
+        bytes += s.len();
+    }
+
+    if start < json_strs.len() {
+        chunks.push(&json_strs[start..]);
+    }
+
+    chunks
+}
+
 /// Validates that an SQL identifier (table or column name) is safe to use.
 ///
 /// # Arguments
@@ -42,6 +238,31 @@ fn validate_sql_identifier(identifier: &str, name: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Error returned by `Db::register_object` and `Db::register_batch_objects`.
+///
+/// Kept distinct from a plain `tokio_postgres::Error` so that
+/// `DedupMode::HashColumn`'s digest-collision check (see
+/// `Db::verify_hash_match`) can't be mistaken for a database/connection
+/// failure by callers — `backend.rs` maps each variant to a different
+/// `JsonRegisterError`.
+#[derive(Debug, thiserror::Error)]
+pub enum RegisterError {
+    /// A genuine database or connection failure.
+    #[error(transparent)]
+    Postgres(#[from] tokio_postgres::Error),
+
+    /// `DedupMode::HashColumn`'s digest matched an existing row, but that
+    /// row's stored payload doesn't match the document being registered.
+    #[error("{0}")]
+    HashMismatch(String),
+
+    /// A stored payload failed to decrypt, decompress, or decode as UTF-8
+    /// (see `Db::decode_payload`) — a tampered or corrupted row, not a
+    /// database or connection failure, so it must not be mistaken for one.
+    #[error("{0}")]
+    Corruption(String),
+}
+
 /// Handles database interactions for registering JSON objects.
 ///
 /// This struct manages the connection pool and executes SQL queries to insert
@@ -49,8 +270,21 @@ fn validate_sql_identifier(identifier: &str, name: &str) -> Result<(), String> {
 /// and minimize round-trips.
 pub struct Db {
     pool: Pool,
+    table_name: String,
     register_query: String,
     register_batch_query: String,
+    lookup_query: String,
+    get_by_id_query: String,
+    record_event_query: String,
+    record_event_batch_query: String,
+    events_since_query: String,
+    schema_statements: Vec<String>,
+    dedup_mode: DedupMode,
+    encryption: Option<EncryptionConfig>,
+    compression: Option<CompressionConfig>,
+    event_log_enabled: bool,
+    batch_limits: BatchLimits,
+    statement_cache_mode: StatementCacheMode,
     queries_executed: AtomicU64,
     query_errors: AtomicU64,
 }
@@ -68,6 +302,24 @@ impl Db {
     /// * `acquire_timeout_secs` - Optional timeout for acquiring connections (default: 5s).
     /// * `idle_timeout_secs` - Optional timeout for idle connections (default: 600s).
     /// * `max_lifetime_secs` - Optional maximum lifetime for connections (default: 1800s).
+    /// * `tls` - Optional TLS configuration, overriding whatever `sslmode`
+    ///   `connection_string` specifies. If `None`, the DSN's `sslmode` param
+    ///   decides (see [`crate::connection::ConnectionConfig::to_tls_config`]),
+    ///   defaulting to `TlsConfig::Disabled` when the DSN has none or doesn't
+    ///   parse as a URL.
+    /// * `batch_limits` - Optional chunking limits for `register_batch_objects` (default: see `BatchLimits`).
+    /// * `dedup_mode` - Optional dedup strategy (default: `DedupMode::JsonbUnique`).
+    /// * `encryption` - Optional at-rest encryption of the payload column. Requires
+    ///   `dedup_mode` to be `DedupMode::HashColumn`, since the hash column — not
+    ///   the (now encrypted) payload — is what dedup relies on.
+    /// * `event_log_enabled` - Whether to record every first-time registration as an
+    ///   append-only event (default: `false`). See [`Db::events_since`].
+    /// * `statement_cache_mode` - Optional prepared-statement caching strategy
+    ///   (default: `StatementCacheMode::Unbounded`). Set to
+    ///   `StatementCacheMode::Disabled` behind a transaction-pooling proxy.
+    /// * `compression` - Optional zstd compression of the payload column
+    ///   (default: disabled). Unlike `encryption`, this works with either
+    ///   `dedup_mode`, since compression is deterministic.
     ///
     /// # Returns
     ///
@@ -82,6 +334,13 @@ impl Db {
         acquire_timeout_secs: Option<u64>,
         idle_timeout_secs: Option<u64>,
         max_lifetime_secs: Option<u64>,
+        tls: Option<TlsConfig>,
+        batch_limits: Option<BatchLimits>,
+        dedup_mode: Option<DedupMode>,
+        encryption: Option<EncryptionConfig>,
+        event_log_enabled: bool,
+        statement_cache_mode: Option<StatementCacheMode>,
+        compression: Option<CompressionConfig>,
     ) -> Result<Self, crate::errors::JsonRegisterError> {
         // Validate SQL identifiers to prevent SQL injection
         validate_sql_identifier(table_name, "table_name")
@@ -91,6 +350,30 @@ impl Db {
         validate_sql_identifier(jsonb_column, "jsonb_column")
             .map_err(crate::errors::JsonRegisterError::Configuration)?;
 
+        let dedup_mode = dedup_mode.unwrap_or_default();
+        if let DedupMode::HashColumn { hash_column } = &dedup_mode {
+            validate_sql_identifier(hash_column, "hash_column")
+                .map_err(crate::errors::JsonRegisterError::Configuration)?;
+        }
+
+        if encryption.is_some() && !matches!(dedup_mode, DedupMode::HashColumn { .. }) {
+            return Err(crate::errors::JsonRegisterError::Configuration(
+                "encryption requires DedupMode::HashColumn, since the payload column is no \
+                 longer usable for dedup once encrypted"
+                    .into(),
+            ));
+        }
+
+        // If the DSN points at a Unix domain socket directory, fail fast
+        // with a clear error rather than an opaque pool-creation failure.
+        // Connection strings that don't parse as a URL (e.g. libpq
+        // keyword/value DSNs) are left to deadpool/tokio-postgres as before,
+        // and also leave `sslmode` undiscoverable below.
+        let parsed_dsn = crate::connection::ConnectionConfig::from_url(connection_string).ok();
+        if let Some(config) = &parsed_dsn {
+            config.validate_socket_path()?;
+        }
+
         // Use provided timeouts or sensible defaults
         let acquire_timeout = Duration::from_secs(acquire_timeout_secs.unwrap_or(5));
         let _idle_timeout = idle_timeout_secs.map(Duration::from_secs);
@@ -112,73 +395,377 @@ impl Db {
             queue_mode: QueueMode::Fifo,
         });
 
-        let pool = cfg.create_pool(Some(Runtime::Tokio1), NoTls).map_err(|e| {
+        // An explicit `tls` override always wins; otherwise fall back to
+        // whatever `sslmode` (and friends) the DSN itself specifies, so a
+        // caller who writes `?sslmode=require` doesn't silently get a plain
+        // connection just because they didn't also call `.tls(...)`.
+        let tls = match tls {
+            Some(tls) => tls,
+            None => match &parsed_dsn {
+                Some(config) => config.to_tls_config()?,
+                None => TlsConfig::default(),
+            },
+        };
+
+        let map_pool_err = |e: deadpool_postgres::CreatePoolError| {
             // Sanitize any connection strings that might appear in error messages
             let error_msg = e.to_string();
             let sanitized_msg = crate::sanitize_connection_string(&error_msg);
             crate::errors::JsonRegisterError::Configuration(sanitized_msg)
-        })?;
+        };
 
-        // Query to register a single object.
-        // It attempts to insert the object. If it exists (ON CONFLICT), it does nothing.
-        // Then it selects the ID, either from the inserted row or the existing row.
-        let register_query = format!(
-            r#"
-            WITH inserted AS (
-                INSERT INTO {table_name} ({jsonb_column})
-                VALUES ($1::jsonb)
-                ON CONFLICT ({jsonb_column}) DO NOTHING
-                RETURNING {id_column}
-            )
-            SELECT {id_column} FROM inserted
-            UNION ALL
-            SELECT {id_column} FROM {table_name}
-            WHERE {jsonb_column} = $2::jsonb
-              AND NOT EXISTS (SELECT 1 FROM inserted)
-            LIMIT 1
-            "#
-        );
+        #[cfg(feature = "tls")]
+        let pool = match &tls {
+            TlsConfig::Disabled => cfg
+                .create_pool(Some(Runtime::Tokio1), NoTls)
+                .map_err(map_pool_err)?,
+            _ => {
+                let connector = build_tls_connector(&tls)?;
+                cfg.create_pool(Some(Runtime::Tokio1), connector)
+                    .map_err(map_pool_err)?
+            }
+        };
 
-        // Query to register a batch of objects.
-        // It uses `unnest` to handle the array of inputs, attempts to insert new ones,
-        // and then joins the results to ensure every input gets its corresponding ID
-        // in the correct order.
-        let register_batch_query = format!(
-            r#"
-            WITH input_objects AS (
-                SELECT
-                    ord as original_order,
-                    value as json_value
-                FROM unnest($1::jsonb[]) WITH ORDINALITY AS t(value, ord)
+        #[cfg(not(feature = "tls"))]
+        let pool = match &tls {
+            TlsConfig::Disabled => cfg
+                .create_pool(Some(Runtime::Tokio1), NoTls)
+                .map_err(map_pool_err)?,
+            _ => {
+                return Err(crate::errors::JsonRegisterError::Configuration(
+                    "TLS configuration requested but the `tls` feature is not enabled".into(),
+                ))
+            }
+        };
+
+        // Query to register a single object. It attempts to insert the object
+        // (identified either by the full JSONB document or, in `HashColumn`
+        // mode, by its digest). If it exists (ON CONFLICT), it does nothing.
+        // Then it selects the ID, either from the inserted row or the
+        // existing row.
+        // Encrypted and/or compressed payloads are bound as raw bytes, so the
+        // `::jsonb` cast used for plaintext payloads doesn't apply.
+        let payload_is_binary = encryption.is_some() || compression.is_some();
+        let payload_cast = if payload_is_binary { "" } else { "::jsonb" };
+        let payload_array_cast = if payload_is_binary { "::bytea[]" } else { "::jsonb[]" };
+        // Used by `HashColumn` mode's queries to read back the stored payload
+        // for the collision check below: a fixed-width digest can
+        // theoretically collide for two different documents, so a hash match
+        // alone isn't proof the stored row is *this* document.
+        let payload_select_cast = if payload_is_binary { "" } else { "::text" };
+
+        let register_query = match &dedup_mode {
+            DedupMode::JsonbUnique => format!(
+                r#"
+                WITH inserted AS (
+                    INSERT INTO {table_name} ({jsonb_column})
+                    VALUES ($1{payload_cast})
+                    ON CONFLICT ({jsonb_column}) DO NOTHING
+                    RETURNING {id_column}
+                )
+                SELECT {id_column} FROM inserted
+                UNION ALL
+                SELECT {id_column} FROM {table_name}
+                WHERE {jsonb_column} = $2{payload_cast}
+                  AND NOT EXISTS (SELECT 1 FROM inserted)
+                LIMIT 1
+                "#
+            ),
+            DedupMode::HashColumn { hash_column } => format!(
+                r#"
+                WITH inserted AS (
+                    INSERT INTO {table_name} ({hash_column}, {jsonb_column})
+                    VALUES ($1, $2{payload_cast})
+                    ON CONFLICT ({hash_column}) DO NOTHING
+                    RETURNING {id_column}, {jsonb_column}{payload_select_cast}
+                )
+                SELECT {id_column}, {jsonb_column}{payload_select_cast} FROM inserted
+                UNION ALL
+                SELECT {id_column}, {jsonb_column}{payload_select_cast} FROM {table_name}
+                WHERE {hash_column} = $1
+                  AND NOT EXISTS (SELECT 1 FROM inserted)
+                LIMIT 1
+                "#
+            ),
+        };
+
+        // Query to register a batch of objects. It uses `unnest` to handle the
+        // array of inputs, attempts to insert new ones, and then joins the
+        // results to ensure every input gets its corresponding ID in the
+        // correct order. `existing` is `DISTINCT ON` the dedup key: without
+        // it, a value repeated N times within `json_strs` that's already in
+        // the table joins against itself N times, so the final `LEFT JOIN`
+        // fans each of those N input rows out to N matches instead of 1.
+        // Callers are expected to dedup within a batch themselves (as
+        // `Register::register_batch_objects` does) for efficiency, but this
+        // keeps a repeated value from corrupting the result either way.
+        let register_batch_query = match &dedup_mode {
+            DedupMode::JsonbUnique => format!(
+                r#"
+                WITH input_objects AS (
+                    SELECT
+                        ord as original_order,
+                        value as json_value
+                    FROM unnest($1{payload_array_cast}) WITH ORDINALITY AS t(value, ord)
+                ),
+                inserted AS (
+                    INSERT INTO {table_name} ({jsonb_column})
+                    SELECT json_value FROM input_objects
+                    ON CONFLICT ({jsonb_column}) DO NOTHING
+                    RETURNING {id_column}, {jsonb_column}
+                ),
+                existing AS (
+                    SELECT DISTINCT ON (t.{jsonb_column}) t.{id_column}, t.{jsonb_column}
+                    FROM {table_name} t
+                    JOIN input_objects io ON t.{jsonb_column} = io.json_value
+                )
+                SELECT COALESCE(i.{id_column}, e.{id_column}) as {id_column}, io.original_order
+                FROM input_objects io
+                LEFT JOIN inserted i ON io.json_value = i.{jsonb_column}
+                LEFT JOIN existing e ON io.json_value = e.{jsonb_column}
+                ORDER BY io.original_order
+                "#
+            ),
+            DedupMode::HashColumn { hash_column } => format!(
+                r#"
+                WITH input_objects AS (
+                    SELECT
+                        ord as original_order,
+                        hash_value,
+                        json_value
+                    FROM unnest($1::text[], $2{payload_array_cast}) WITH ORDINALITY AS t(hash_value, json_value, ord)
+                ),
+                inserted AS (
+                    INSERT INTO {table_name} ({hash_column}, {jsonb_column})
+                    SELECT hash_value, json_value FROM input_objects
+                    ON CONFLICT ({hash_column}) DO NOTHING
+                    RETURNING {id_column}, {hash_column}, {jsonb_column}
+                ),
+                existing AS (
+                    SELECT DISTINCT ON (t.{hash_column}) t.{id_column}, t.{hash_column}, t.{jsonb_column}
+                    FROM {table_name} t
+                    JOIN input_objects io ON t.{hash_column} = io.hash_value
+                )
+                SELECT COALESCE(i.{id_column}, e.{id_column}) as {id_column},
+                       COALESCE(i.{jsonb_column}, e.{jsonb_column}){payload_select_cast} as stored_payload,
+                       io.original_order
+                FROM input_objects io
+                LEFT JOIN inserted i ON io.hash_value = i.{hash_column}
+                LEFT JOIN existing e ON io.hash_value = e.{hash_column}
+                ORDER BY io.original_order
+                "#
             ),
-            inserted AS (
-                INSERT INTO {table_name} ({jsonb_column})
-                SELECT json_value FROM input_objects
-                ON CONFLICT ({jsonb_column}) DO NOTHING
-                RETURNING {id_column}, {jsonb_column}
+        };
+
+        // Query for a read-only existence check: unlike `register_query`, this
+        // never inserts, so it can be used to test negative-cache candidates
+        // without creating a row.
+        let lookup_query = match &dedup_mode {
+            DedupMode::JsonbUnique => format!(
+                r#"
+                SELECT {id_column} FROM {table_name}
+                WHERE {jsonb_column} = $1{payload_cast}
+                LIMIT 1
+                "#
             ),
-            existing AS (
-                SELECT t.{id_column}, t.{jsonb_column}
-                FROM {table_name} t
-                JOIN input_objects io ON t.{jsonb_column} = io.json_value
+            DedupMode::HashColumn { hash_column } => format!(
+                r#"
+                SELECT {id_column} FROM {table_name}
+                WHERE {hash_column} = $1
+                LIMIT 1
+                "#
+            ),
+        };
+
+        // Query for `get_object`. Encrypted/compressed payloads are returned
+        // as raw bytes for in-process decoding; plaintext payloads are cast
+        // to `text` so the driver hands back a `String` directly.
+        let get_by_id_query = if payload_is_binary {
+            format!(
+                r#"
+                SELECT {jsonb_column} FROM {table_name}
+                WHERE {id_column} = $1
+                "#
             )
-            SELECT COALESCE(i.{id_column}, e.{id_column}) as {id_column}, io.original_order
-            FROM input_objects io
-            LEFT JOIN inserted i ON io.json_value = i.{jsonb_column}
-            LEFT JOIN existing e ON io.json_value = e.{jsonb_column}
-            ORDER BY io.original_order
+        } else {
+            format!(
+                r#"
+                SELECT {jsonb_column}::text FROM {table_name}
+                WHERE {id_column} = $1
+                "#
+            )
+        };
+
+        // Event log queries. `record_event_query` is idempotent via a
+        // `UNIQUE (table_name, id)` constraint on the events table, so it is
+        // safe to call on every registration regardless of whether the
+        // object was actually new — only the first insert for a given id
+        // sticks.
+        let events_table = crate::schema::EVENTS_TABLE;
+        let record_event_query = format!(
+            r#"
+            INSERT INTO {events_table} (table_name, id, digest, canonical, registered_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (table_name, id) DO NOTHING
+            "#
+        );
+        // Batched form of `record_event_query` for `send_batch_chunk`: one
+        // round-trip logs every row in the chunk via `unnest`, the same
+        // pattern `register_batch_query` uses to avoid N+1 inserts.
+        let record_event_batch_query = format!(
+            r#"
+            INSERT INTO {events_table} (table_name, id, digest, canonical, registered_at)
+            SELECT $1, id, digest, canonical, $5
+            FROM unnest($2::int4[], $3::text[], $4::text[]) AS t(id, digest, canonical)
+            ON CONFLICT (table_name, id) DO NOTHING
             "#
         );
+        let events_since_query = format!(
+            r#"
+            SELECT seq, id, digest, canonical, registered_at FROM {events_table}
+            WHERE table_name = $1 AND seq > $2
+            ORDER BY seq
+            LIMIT $3
+            "#
+        );
+
+        let schema_statements = crate::schema::ddl_statements(
+            table_name,
+            id_column,
+            jsonb_column,
+            &dedup_mode,
+            payload_is_binary,
+            event_log_enabled,
+        );
 
         Ok(Self {
             pool,
+            table_name: table_name.to_string(),
             register_query,
             register_batch_query,
+            lookup_query,
+            get_by_id_query,
+            record_event_query,
+            record_event_batch_query,
+            events_since_query,
+            schema_statements,
+            dedup_mode,
+            encryption,
+            compression,
+            event_log_enabled,
+            batch_limits: batch_limits.unwrap_or_default(),
+            statement_cache_mode: statement_cache_mode.unwrap_or_default(),
             queries_executed: AtomicU64::new(0),
             query_errors: AtomicU64::new(0),
         })
     }
 
+    /// Prepares `sql` according to `self.statement_cache_mode`: cached and
+    /// reused for the life of the pooled connection, or issued fresh every
+    /// call.
+    async fn prepare_statement(
+        &self,
+        client: &deadpool_postgres::Client,
+        sql: &str,
+    ) -> Result<tokio_postgres::Statement, tokio_postgres::Error> {
+        match self.statement_cache_mode {
+            StatementCacheMode::Unbounded => client.prepare_cached(sql).await,
+            StatementCacheMode::Disabled => client.prepare(sql).await,
+        }
+    }
+
+    /// Creates the target table (and the small migrations metadata table)
+    /// if they don't already exist, using the column names and dedup mode
+    /// this `Db` was constructed with.
+    ///
+    /// Every generated statement is idempotent, so calling this repeatedly
+    /// against an already-migrated table is a no-op. This does not replace a
+    /// real migration tool for schema changes beyond what `Db` itself needs
+    /// (e.g. additional application-specific columns).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `()` on success, or a `tokio_postgres::Error`.
+    pub async fn ensure_schema(&self) -> Result<(), tokio_postgres::Error> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .map_err(|e: PoolError<tokio_postgres::Error>| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                match e {
+                    PoolError::Backend(db_err) => db_err,
+                    PoolError::Timeout(_) => tokio_postgres::Error::__private_api_timeout(),
+                    _ => tokio_postgres::Error::__private_api_timeout(),
+                }
+            })?;
+
+        for statement in &self.schema_statements {
+            self.queries_executed.fetch_add(1, Ordering::Relaxed);
+            if let Err(e) = client.batch_execute(statement).await {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the payload column stores raw bytes (encrypted and/or
+    /// compressed) rather than plaintext JSONB.
+    fn payload_is_binary(&self) -> bool {
+        self.encryption.is_some() || self.compression.is_some()
+    }
+
+    /// Encodes `json_str` for storage, compressing and/or encrypting it as
+    /// configured (compression first, so encryption — if also configured —
+    /// only ever sees already-compressed bytes). Returns `None` when the
+    /// payload column stores plaintext JSONB directly.
+    fn encode_payload(&self, json_str: &str) -> Option<Vec<u8>> {
+        if !self.payload_is_binary() {
+            return None;
+        }
+        let mut bytes = json_str.as_bytes().to_vec();
+        if let Some(compression) = &self.compression {
+            bytes = compression.compress(&bytes);
+        }
+        if let Some(encryption) = &self.encryption {
+            bytes = encryption.encrypt(&bytes);
+        }
+        Some(bytes)
+    }
+
+    /// Reverses `encode_payload`: decrypts (if configured) then decompresses
+    /// (if configured) stored bytes back into the original canonical JSON
+    /// string.
+    fn decode_payload(&self, mut bytes: Vec<u8>) -> Result<String, RegisterError> {
+        if let Some(encryption) = &self.encryption {
+            bytes = encryption.decrypt(&bytes).map_err(|_| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                RegisterError::Corruption(format!(
+                    "table {:?}: stored payload failed to decrypt (tampered ciphertext or wrong key)",
+                    self.table_name
+                ))
+            })?;
+        }
+        if let Some(compression) = &self.compression {
+            bytes = compression.decompress(&bytes).map_err(|_| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                RegisterError::Corruption(format!(
+                    "table {:?}: stored payload failed to decompress (corrupted row)",
+                    self.table_name
+                ))
+            })?;
+        }
+        String::from_utf8(bytes).map_err(|_| {
+            self.query_errors.fetch_add(1, Ordering::Relaxed);
+            RegisterError::Corruption(format!(
+                "table {:?}: stored payload is not valid UTF-8 after decoding (corrupted row)",
+                self.table_name
+            ))
+        })
+    }
+
     /// Registers a single JSON object string in the database.
     ///
     /// # Arguments
@@ -187,8 +774,13 @@ impl Db {
     ///
     /// # Returns
     ///
-    /// A `Result` containing the ID (i32) or a `tokio_postgres::Error`.
-    pub async fn register_object(&self, json_str: &str) -> Result<i32, tokio_postgres::Error> {
+    /// A `Result` containing the ID (i32), or a `RegisterError` — either a
+    /// `tokio_postgres::Error` from the database itself, a
+    /// `RegisterError::HashMismatch` if `DedupMode::HashColumn`'s collision
+    /// check fails (see `Db::verify_hash_match`), or a
+    /// `RegisterError::Corruption` if the stored payload it compares against
+    /// can't be decoded.
+    pub async fn register_object(&self, json_str: &str) -> Result<i32, RegisterError> {
         self.queries_executed.fetch_add(1, Ordering::Relaxed);
 
         let client = self
@@ -204,12 +796,283 @@ impl Db {
                 }
             })?;
 
-        let result = client
-            .query_one(&self.register_query, &[&json_str, &json_str])
-            .await;
+        let statement = self
+            .prepare_statement(&client, &self.register_query)
+            .await
+            .map_err(|e| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                e
+            })?;
+
+        let result = match &self.dedup_mode {
+            DedupMode::JsonbUnique => match self.encode_payload(json_str) {
+                Some(bytes) => client.query_one(&statement, &[&bytes, &bytes]).await,
+                None => {
+                    client
+                        .query_one(&statement, &[&json_str, &json_str])
+                        .await
+                }
+            },
+            DedupMode::HashColumn { .. } => {
+                // The hash column always digests the canonical *plaintext*,
+                // so identical objects dedup to one row even though each
+                // encrypted payload carries its own random nonce.
+                let hash = crate::canonicalise::digest_sha256(json_str);
+                match self.encode_payload(json_str) {
+                    Some(bytes) => client.query_one(&statement, &[&hash, &bytes]).await,
+                    None => client.query_one(&statement, &[&hash, &json_str]).await,
+                }
+            }
+        };
+
+        let id: i32 = match result {
+            Ok(row) => {
+                let id = row.get(0);
+                if matches!(self.dedup_mode, DedupMode::HashColumn { .. }) {
+                    self.verify_hash_match(&row, json_str)?;
+                }
+                id
+            }
+            Err(e) => {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                return Err(e.into());
+            }
+        };
+
+        if self.event_log_enabled {
+            let digest = crate::canonicalise::digest_sha256(json_str);
+            self.record_event(&client, id, &digest, json_str).await?;
+        }
+
+        Ok(id)
+    }
+
+    /// Guards against a hash-digest collision in `DedupMode::HashColumn`: a
+    /// matching digest alone doesn't prove `row`'s stored payload is actually
+    /// `expected`, since the hash column is fixed-width and therefore lossy.
+    /// Decrypts first if `encryption` is configured, since stored ciphertext
+    /// can't be compared directly (each encryption uses its own nonce).
+    ///
+    /// Collisions are astronomically unlikely with SHA-256, so this is a
+    /// defensive check rather than an expected code path.
+    fn verify_hash_match(
+        &self,
+        row: &tokio_postgres::Row,
+        expected: &str,
+    ) -> Result<(), RegisterError> {
+        let stored: String = if self.payload_is_binary() {
+            let bytes: Vec<u8> = row.get(1);
+            self.decode_payload(bytes)?
+        } else {
+            row.get(1)
+        };
+
+        if stored != expected {
+            self.query_errors.fetch_add(1, Ordering::Relaxed);
+            return Err(RegisterError::HashMismatch(format!(
+                "table {:?}: digest matched an existing row but its stored payload differs from \
+                 the submitted document (hash collision or corrupted row)",
+                self.table_name
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Records a first-time registration in the append-only event log.
+    ///
+    /// Idempotent via the events table's `UNIQUE (table_name, id)`
+    /// constraint: calling this for an `id` that's already been logged is a
+    /// no-op, so callers don't need to know whether a registration was
+    /// actually new before calling it.
+    async fn record_event(
+        &self,
+        client: &deadpool_postgres::Client,
+        id: i32,
+        digest: &str,
+        canonical: &str,
+    ) -> Result<(), tokio_postgres::Error> {
+        self.queries_executed.fetch_add(1, Ordering::Relaxed);
+
+        let registered_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        let statement = self
+            .prepare_statement(client, &self.record_event_query)
+            .await
+            .map_err(|e| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                e
+            })?;
+
+        match client
+            .execute(
+                &statement,
+                &[&self.table_name, &id, &digest, &canonical, &registered_at],
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                Err(e)
+            }
+        }
+    }
+
+    /// Batched form of `record_event` for `send_batch_chunk`: logs every
+    /// `(id, digest, canonical)` row in one `unnest`-based round-trip instead
+    /// of one `record_event` call per row, the same round-trip-minimization
+    /// this file's batch chunking relies on elsewhere.
+    ///
+    /// Idempotent the same way `record_event` is, via the events table's
+    /// `UNIQUE (table_name, id)` constraint.
+    async fn record_events(
+        &self,
+        client: &deadpool_postgres::Client,
+        ids: &[i32],
+        digests: &[String],
+        canonicals: &[String],
+    ) -> Result<(), tokio_postgres::Error> {
+        self.queries_executed.fetch_add(1, Ordering::Relaxed);
+
+        let registered_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        let statement = self
+            .prepare_statement(client, &self.record_event_batch_query)
+            .await
+            .map_err(|e| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                e
+            })?;
+
+        match client
+            .execute(
+                &statement,
+                &[&self.table_name, &ids, &digests, &canonicals, &registered_at],
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                Err(e)
+            }
+        }
+    }
+
+    /// Fetches a page of the append-only event log after `seq`, in order.
+    ///
+    /// Pass `0` as `seq` to start from the beginning. Paginate a large
+    /// registry by repeatedly calling this with the last returned event's
+    /// `seq`, bounding memory and round-trip size regardless of how many
+    /// events exist in total.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing up to `page_size` events, ordered by `seq`
+    /// ascending, or a `tokio_postgres::Error`.
+    pub async fn events_since(
+        &self,
+        seq: i64,
+        page_size: i64,
+    ) -> Result<Vec<crate::events::Event>, tokio_postgres::Error> {
+        self.queries_executed.fetch_add(1, Ordering::Relaxed);
+
+        let client = self
+            .pool
+            .get()
+            .await
+            .map_err(|e: PoolError<tokio_postgres::Error>| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                match e {
+                    PoolError::Backend(db_err) => db_err,
+                    PoolError::Timeout(_) => tokio_postgres::Error::__private_api_timeout(),
+                    _ => tokio_postgres::Error::__private_api_timeout(),
+                }
+            })?;
+
+        let statement = self
+            .prepare_statement(&client, &self.events_since_query)
+            .await
+            .map_err(|e| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                e
+            })?;
+
+        let rows = match client
+            .query(&statement, &[&self.table_name, &seq, &page_size])
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                return Err(e);
+            }
+        };
+
+        Ok(rows
+            .iter()
+            .map(|row| crate::events::Event {
+                seq: row.get(0),
+                id: row.get(1),
+                digest: row.get(2),
+                canonical: row.get(3),
+                registered_at: row.get::<_, i64>(4) as u64,
+            })
+            .collect())
+    }
+
+    /// Checks whether a JSON object string is already registered, without
+    /// inserting it if it is not.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_str` - The canonicalised JSON string.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `Some(id)` if the object is already registered,
+    /// `None` otherwise, or a `tokio_postgres::Error`.
+    pub async fn lookup_object(&self, json_str: &str) -> Result<Option<i32>, tokio_postgres::Error> {
+        self.queries_executed.fetch_add(1, Ordering::Relaxed);
+
+        let client = self
+            .pool
+            .get()
+            .await
+            .map_err(|e: PoolError<tokio_postgres::Error>| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                match e {
+                    PoolError::Backend(db_err) => db_err,
+                    PoolError::Timeout(_) => tokio_postgres::Error::__private_api_timeout(),
+                    _ => tokio_postgres::Error::__private_api_timeout(),
+                }
+            })?;
+
+        let statement = self
+            .prepare_statement(&client, &self.lookup_query)
+            .await
+            .map_err(|e| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                e
+            })?;
+
+        let result = match &self.dedup_mode {
+            DedupMode::JsonbUnique => client.query_opt(&statement, &[&json_str]).await,
+            DedupMode::HashColumn { .. } => {
+                let hash = crate::canonicalise::digest_sha256(json_str);
+                client.query_opt(&statement, &[&hash]).await
+            }
+        };
 
         match result {
-            Ok(row) => Ok(row.get(0)),
+            Ok(row) => Ok(row.map(|r| r.get(0))),
             Err(e) => {
                 self.query_errors.fetch_add(1, Ordering::Relaxed);
                 Err(e)
@@ -217,23 +1080,107 @@ impl Db {
         }
     }
 
+    /// Fetches a previously registered object's canonical JSON string by id.
+    ///
+    /// When `encryption` and/or `compression` is configured, the stored
+    /// bytes are decrypted/decompressed before being returned; a failure to
+    /// do so (tampered ciphertext, wrong key, or corrupt compressed data) is
+    /// reported as `RegisterError::Corruption`, kept distinct from a
+    /// `RegisterError::Postgres` connection failure for the same reason
+    /// `Db::verify_hash_match` keeps `HashMismatch` distinct (see
+    /// `RegisterError`'s doc comment).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `Some(canonical json)` if `id` exists, `None`
+    /// otherwise, or a `RegisterError`.
+    pub async fn get_object(&self, id: i32) -> Result<Option<String>, RegisterError> {
+        self.queries_executed.fetch_add(1, Ordering::Relaxed);
+
+        let client = self
+            .pool
+            .get()
+            .await
+            .map_err(|e: PoolError<tokio_postgres::Error>| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                match e {
+                    PoolError::Backend(db_err) => db_err,
+                    PoolError::Timeout(_) => tokio_postgres::Error::__private_api_timeout(),
+                    _ => tokio_postgres::Error::__private_api_timeout(),
+                }
+            })?;
+
+        let statement = self
+            .prepare_statement(&client, &self.get_by_id_query)
+            .await
+            .map_err(|e| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                e
+            })?;
+
+        let row = match client.query_opt(&statement, &[&id]).await {
+            Ok(row) => row,
+            Err(e) => {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                return Err(e.into());
+            }
+        };
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        if self.payload_is_binary() {
+            let bytes: Vec<u8> = row.get(0);
+            Ok(Some(self.decode_payload(bytes)?))
+        } else {
+            Ok(Some(row.get(0)))
+        }
+    }
+
     /// Registers a batch of JSON object strings in the database.
     ///
+    /// Internally splits `json_strs` into contiguous chunks bounded by
+    /// `self.batch_limits` (element count and aggregate byte size), issuing one
+    /// `register_batch_query` round-trip per chunk and concatenating the
+    /// resulting IDs. The returned `Vec<i32>` is always aligned 1:1 with the
+    /// input, regardless of how many chunks were needed, and each chunk's query
+    /// is counted in `queries_executed`.
+    ///
+    /// These chunk limits are not a Postgres bind-parameter workaround — see
+    /// [`BatchLimits`]'s doc comment for why that limit doesn't apply here.
+    ///
     /// # Arguments
     ///
     /// * `json_strs` - A slice of canonicalised JSON strings.
     ///
     /// # Returns
     ///
-    /// A `Result` containing a vector of IDs or a `tokio_postgres::Error`.
+    /// A `Result` containing a vector of IDs, or a `RegisterError` — either a
+    /// `tokio_postgres::Error` from the database itself, a
+    /// `RegisterError::HashMismatch` if `DedupMode::HashColumn`'s collision
+    /// check fails (see `Db::verify_hash_match`), or a
+    /// `RegisterError::Corruption` if a stored payload it compares against
+    /// can't be decoded. On failure, the first error
+    /// encountered is returned and any remaining chunks are not attempted.
     pub async fn register_batch_objects(
         &self,
         json_strs: &[String],
-    ) -> Result<Vec<i32>, tokio_postgres::Error> {
+    ) -> Result<Vec<i32>, RegisterError> {
         if json_strs.is_empty() {
             return Ok(vec![]);
         }
 
+        let mut ids = Vec::with_capacity(json_strs.len());
+        for chunk in chunk_batch(json_strs, &self.batch_limits) {
+            let chunk_ids = self.send_batch_chunk(chunk).await?;
+            ids.extend(chunk_ids);
+        }
+        Ok(ids)
+    }
+
+    /// Sends a single chunk of `json_strs` to Postgres in one round-trip.
+    async fn send_batch_chunk(&self, json_strs: &[String]) -> Result<Vec<i32>, RegisterError> {
         self.queries_executed.fetch_add(1, Ordering::Relaxed);
 
         let client = self
@@ -249,24 +1196,69 @@ impl Db {
                 }
             })?;
 
-        let result = client
-            .query(&self.register_batch_query, &[&json_strs])
-            .await;
+        let statement = self
+            .prepare_statement(&client, &self.register_batch_query)
+            .await
+            .map_err(|e| {
+                self.query_errors.fetch_add(1, Ordering::Relaxed);
+                e
+            })?;
 
-        match result {
+        let result = match &self.dedup_mode {
+            DedupMode::JsonbUnique => {
+                if self.payload_is_binary() {
+                    let payloads: Vec<Vec<u8>> = json_strs
+                        .iter()
+                        .map(|s| self.encode_payload(s).expect("payload_is_binary() is true"))
+                        .collect();
+                    client.query(&statement, &[&payloads]).await
+                } else {
+                    client.query(&statement, &[&json_strs]).await
+                }
+            }
+            DedupMode::HashColumn { .. } => {
+                let hashes: Vec<String> = json_strs
+                    .iter()
+                    .map(|s| crate::canonicalise::digest_sha256(s))
+                    .collect();
+                if self.payload_is_binary() {
+                    let payloads: Vec<Vec<u8>> = json_strs
+                        .iter()
+                        .map(|s| self.encode_payload(s).expect("payload_is_binary() is true"))
+                        .collect();
+                    client.query(&statement, &[&hashes, &payloads]).await
+                } else {
+                    client.query(&statement, &[&hashes, &json_strs]).await
+                }
+            }
+        };
+
+        let ids: Vec<i32> = match result {
             Ok(rows) => {
-                let mut ids = Vec::with_capacity(rows.len());
-                for row in rows {
-                    let id: i32 = row.get(0);
-                    ids.push(id);
+                if matches!(self.dedup_mode, DedupMode::HashColumn { .. }) {
+                    // Rows are ordered by `original_order`, so they line up
+                    // 1:1 with `json_strs` already.
+                    for (row, json_str) in rows.iter().zip(json_strs.iter()) {
+                        self.verify_hash_match(row, json_str)?;
+                    }
                 }
-                Ok(ids)
+                rows.iter().map(|row| row.get(0)).collect()
             }
             Err(e) => {
                 self.query_errors.fetch_add(1, Ordering::Relaxed);
-                Err(e)
+                return Err(e.into());
             }
+        };
+
+        if self.event_log_enabled {
+            let digests: Vec<String> = json_strs
+                .iter()
+                .map(|s| crate::canonicalise::digest_sha256(s))
+                .collect();
+            self.record_events(&client, &ids, &digests, json_strs).await?;
         }
+
+        Ok(ids)
     }
 
     /// Returns the current size of the connection pool.
@@ -331,6 +1323,135 @@ impl Db {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_tls_config_default_is_disabled() {
+        assert!(matches!(TlsConfig::default(), TlsConfig::Disabled));
+    }
+
+    #[test]
+    fn test_dedup_mode_default_is_jsonb_unique() {
+        assert!(matches!(DedupMode::default(), DedupMode::JsonbUnique));
+    }
+
+    #[test]
+    fn test_statement_cache_mode_default_is_unbounded() {
+        assert!(matches!(
+            StatementCacheMode::default(),
+            StatementCacheMode::Unbounded
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_new_rejects_encryption_without_hash_column_dedup() {
+        let encryption =
+            crate::encryption::EncryptionConfig::new(crate::encryption::Cipher::Aes256Gcm, vec![0u8; 32])
+                .unwrap();
+
+        let result = Db::new(
+            "postgresql://localhost/test",
+            "objects",
+            "id",
+            "data",
+            5,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(DedupMode::JsonbUnique),
+            Some(encryption),
+            false,
+            None,
+            None,
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::errors::JsonRegisterError::Configuration(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_new_allows_compression_with_jsonb_unique_dedup() {
+        // Unlike encryption, compression is deterministic, so it doesn't
+        // need `DedupMode::HashColumn` to keep dedup working.
+        let compression = crate::compression::CompressionConfig::new(3).unwrap();
+
+        let result = Db::new(
+            "postgresql://localhost/test",
+            "objects",
+            "id",
+            "data",
+            5,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(DedupMode::JsonbUnique),
+            None,
+            false,
+            None,
+            Some(compression),
+        )
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_chunk_batch_respects_max_elements() {
+        let items: Vec<String> = (0..5).map(|i| i.to_string()).collect();
+        let limits = BatchLimits {
+            max_elements: 2,
+            max_bytes: usize::MAX,
+        };
+
+        let chunks = chunk_batch(&items, &limits);
+        let sizes: Vec<usize> = chunks.iter().map(|c| c.len()).collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+
+        let flattened: Vec<&String> = chunks.into_iter().flatten().collect();
+        let expected: Vec<&String> = items.iter().collect();
+        assert_eq!(flattened, expected);
+    }
+
+    #[test]
+    fn test_chunk_batch_respects_max_bytes() {
+        let items = vec!["aaaa".to_string(), "bb".to_string(), "cc".to_string()];
+        let limits = BatchLimits {
+            max_elements: usize::MAX,
+            max_bytes: 5,
+        };
+
+        // "aaaa" (4 bytes) fits alone; adding "bb" would exceed 5 bytes, so it
+        // starts a new chunk. "bb" + "cc" (4 bytes) fits together.
+        let chunks = chunk_batch(&items, &limits);
+        let sizes: Vec<usize> = chunks.iter().map(|c| c.len()).collect();
+        assert_eq!(sizes, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_chunk_batch_oversized_single_element_gets_own_chunk() {
+        let items = vec!["a".repeat(100)];
+        let limits = BatchLimits {
+            max_elements: 10,
+            max_bytes: 10,
+        };
+
+        let chunks = chunk_batch(&items, &limits);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].len(), 1);
+    }
+
+    #[test]
+    fn test_chunk_batch_empty_input() {
+        let items: Vec<String> = vec![];
+        let chunks = chunk_batch(&items, &BatchLimits::default());
+        assert!(chunks.is_empty());
+    }
+
     #[test]
     fn test_validate_sql_identifier_valid() {
         // Valid identifiers should pass
@@ -0,0 +1,173 @@
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{AeadCore, Aes256Gcm};
+use chacha20poly1305::XChaCha20Poly1305;
+
+use crate::errors::JsonRegisterError;
+
+/// The authenticated cipher used for at-rest encryption of a stored payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cipher {
+    /// AES-256-GCM with a 96-bit random nonce.
+    Aes256Gcm,
+    /// XChaCha20-Poly1305 with a 192-bit random nonce.
+    XChaCha20Poly1305,
+}
+
+impl Cipher {
+    fn nonce_len(self) -> usize {
+        match self {
+            Cipher::Aes256Gcm => 12,
+            Cipher::XChaCha20Poly1305 => 24,
+        }
+    }
+}
+
+/// Configures transparent at-rest encryption of the stored payload column.
+///
+/// Deduplication is unaffected by encryption: pair this with
+/// `DedupMode::HashColumn` so the unique-indexed hash column stores the
+/// digest of the canonical *plaintext*, while the payload column stores only
+/// `nonce || ciphertext` under a fresh random nonce per write. Identical
+/// objects therefore still collapse to a single row and ID even though their
+/// ciphertexts differ every time they're written.
+#[derive(Clone)]
+pub struct EncryptionConfig {
+    cipher: Cipher,
+    key: Vec<u8>,
+}
+
+/// Both supported ciphers take a 256-bit key.
+const KEY_LEN: usize = 32;
+
+impl EncryptionConfig {
+    /// Creates a new encryption configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `cipher` - Which authenticated cipher to use.
+    /// * `key` - The raw key bytes. Must be 32 bytes for both supported ciphers.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new config, or a `JsonRegisterError` if the
+    /// key is the wrong length.
+    pub fn new(cipher: Cipher, key: Vec<u8>) -> Result<Self, JsonRegisterError> {
+        if key.len() != KEY_LEN {
+            return Err(JsonRegisterError::Configuration(format!(
+                "encryption key must be {KEY_LEN} bytes, got {}",
+                key.len()
+            )));
+        }
+        Ok(Self { cipher, key })
+    }
+
+    /// Encrypts `plaintext` under a fresh random nonce, returning
+    /// `nonce || ciphertext || tag`.
+    pub(crate) fn encrypt(&self, plaintext: &[u8]) -> Vec<u8> {
+        match self.cipher {
+            Cipher::Aes256Gcm => {
+                let cipher = Aes256Gcm::new_from_slice(&self.key)
+                    .expect("key length already validated in EncryptionConfig::new");
+                let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+                let ciphertext = cipher
+                    .encrypt(&nonce, plaintext)
+                    .expect("AES-256-GCM encryption of a valid plaintext cannot fail");
+                [nonce.as_slice(), &ciphertext].concat()
+            }
+            Cipher::XChaCha20Poly1305 => {
+                let cipher = XChaCha20Poly1305::new_from_slice(&self.key)
+                    .expect("key length already validated in EncryptionConfig::new");
+                let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+                let ciphertext = cipher
+                    .encrypt(&nonce, plaintext)
+                    .expect("XChaCha20-Poly1305 encryption of a valid plaintext cannot fail");
+                [nonce.as_slice(), &ciphertext].concat()
+            }
+        }
+    }
+
+    /// Decrypts a `nonce || ciphertext || tag` payload produced by [`Self::encrypt`].
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the original plaintext, or a `JsonRegisterError`
+    /// if the payload is truncated or fails authentication (e.g. tampered
+    /// ciphertext or the wrong key).
+    pub(crate) fn decrypt(&self, payload: &[u8]) -> Result<Vec<u8>, JsonRegisterError> {
+        let nonce_len = self.cipher.nonce_len();
+        if payload.len() < nonce_len {
+            return Err(JsonRegisterError::RuntimeError(
+                "encrypted payload is shorter than the cipher's nonce".into(),
+            ));
+        }
+        let (nonce_bytes, ciphertext) = payload.split_at(nonce_len);
+
+        match self.cipher {
+            Cipher::Aes256Gcm => {
+                let cipher = Aes256Gcm::new_from_slice(&self.key)
+                    .expect("key length already validated in EncryptionConfig::new");
+                cipher
+                    .decrypt(nonce_bytes.into(), ciphertext)
+                    .map_err(|_| JsonRegisterError::RuntimeError("decryption failed".into()))
+            }
+            Cipher::XChaCha20Poly1305 => {
+                let cipher = XChaCha20Poly1305::new_from_slice(&self.key)
+                    .expect("key length already validated in EncryptionConfig::new");
+                cipher
+                    .decrypt(nonce_bytes.into(), ciphertext)
+                    .map_err(|_| JsonRegisterError::RuntimeError("decryption failed".into()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_rejects_wrong_key_length() {
+        assert!(EncryptionConfig::new(Cipher::Aes256Gcm, vec![0u8; 16]).is_err());
+    }
+
+    #[test]
+    fn test_aes_gcm_round_trip() {
+        let config = EncryptionConfig::new(Cipher::Aes256Gcm, vec![7u8; KEY_LEN]).unwrap();
+        let plaintext = b"{\"name\":\"Alice\"}";
+        let ciphertext = config.encrypt(plaintext);
+        assert_ne!(ciphertext, plaintext);
+        assert_eq!(config.decrypt(&ciphertext).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_xchacha20_poly1305_round_trip() {
+        let config =
+            EncryptionConfig::new(Cipher::XChaCha20Poly1305, vec![9u8; KEY_LEN]).unwrap();
+        let plaintext = b"{\"name\":\"Bob\"}";
+        let ciphertext = config.encrypt(plaintext);
+        assert_ne!(ciphertext, plaintext);
+        assert_eq!(config.decrypt(&ciphertext).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn test_encrypt_uses_random_nonce_each_call() {
+        let config = EncryptionConfig::new(Cipher::Aes256Gcm, vec![1u8; KEY_LEN]).unwrap();
+        let plaintext = b"same plaintext";
+        assert_ne!(config.encrypt(plaintext), config.encrypt(plaintext));
+    }
+
+    #[test]
+    fn test_decrypt_rejects_tampered_ciphertext() {
+        let config = EncryptionConfig::new(Cipher::Aes256Gcm, vec![3u8; KEY_LEN]).unwrap();
+        let mut ciphertext = config.encrypt(b"hello");
+        let last = ciphertext.len() - 1;
+        ciphertext[last] ^= 0xff;
+        assert!(config.decrypt(&ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_rejects_truncated_payload() {
+        let config = EncryptionConfig::new(Cipher::Aes256Gcm, vec![3u8; KEY_LEN]).unwrap();
+        assert!(config.decrypt(&[0u8; 4]).is_err());
+    }
+}
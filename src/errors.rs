@@ -26,6 +26,18 @@ pub enum JsonRegisterError {
     /// An error occurred during Python serialization/deserialization.
     #[error("Python serialization error: {0}")]
     SerializationError(String),
+
+    /// A value couldn't be canonicalised under the requested profile (e.g. a
+    /// `NaN`/`Infinity` number under `CanonicalForm::Jcs`, or a non-integer
+    /// value under `canonicalise_strict`).
+    #[error("Canonicalisation error: {0}")]
+    Canonicalisation(String),
+
+    /// Stored data failed a consistency check unrelated to any database or
+    /// connection failure (e.g. `DedupMode::HashColumn`'s digest-collision
+    /// check finding a stored payload that doesn't match its digest).
+    #[error("Integrity error: {0}")]
+    Integrity(String),
 }
 
 #[cfg(feature = "python")]
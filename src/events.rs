@@ -0,0 +1,25 @@
+/// A single, ordered record of a first-time object registration.
+///
+/// Two registries that replay the same ordered sequence of `Event`s via
+/// [`crate::Register::import_events`] converge on identical digest→row
+/// mappings: `import_events` re-registers each event's already-canonicalised
+/// `canonical` text directly (rather than re-canonicalising from a JSON
+/// value), and registration is idempotent, so replaying the same stream
+/// again adds no new rows. The ids are assigned by the importing registry and
+/// need not equal each event's `id`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Monotonically increasing position in the event log, used as the
+    /// cursor for `events_since`.
+    pub seq: i64,
+    /// The id assigned when this object was first registered.
+    pub id: i32,
+    /// Hex-encoded SHA-256 digest of `canonical` (see
+    /// [`crate::canonicalise::digest_sha256`]), recorded so importers can
+    /// verify the event wasn't corrupted or tampered with in transit.
+    pub digest: String,
+    /// The canonicalised JSON string that was registered.
+    pub canonical: String,
+    /// Unix timestamp (seconds) when the event was recorded.
+    pub registered_at: u64,
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI32, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::backend::RegisterBackend;
+use crate::errors::JsonRegisterError;
+
+/// An in-memory `RegisterBackend` for tests and local development.
+///
+/// Maps canonicalised JSON strings to monotonically-issued ids, guarded by a
+/// mutex. No database is required, so the full `Register` test suite can run
+/// against it without `#[ignore]`.
+pub struct InMemoryBackend {
+    entries: Mutex<HashMap<String, i32>>,
+    by_id: Mutex<HashMap<i32, String>>,
+    next_id: AtomicI32,
+    queries_executed: AtomicU64,
+}
+
+impl InMemoryBackend {
+    /// Creates a new, empty `InMemoryBackend`. Ids are issued starting at 1.
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            by_id: Mutex::new(HashMap::new()),
+            next_id: AtomicI32::new(1),
+            queries_executed: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of distinct objects currently stored.
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("entries mutex poisoned").len()
+    }
+
+    /// Returns `true` if no objects are currently stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for InMemoryBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegisterBackend for InMemoryBackend {
+    async fn register_one(&self, canonical: &str) -> Result<i32, JsonRegisterError> {
+        self.queries_executed.fetch_add(1, Ordering::Relaxed);
+
+        let mut entries = self.entries.lock().expect("entries mutex poisoned");
+        if let Some(&id) = entries.get(canonical) {
+            return Ok(id);
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        entries.insert(canonical.to_string(), id);
+        self.by_id
+            .lock()
+            .expect("by_id mutex poisoned")
+            .insert(id, canonical.to_string());
+        Ok(id)
+    }
+
+    async fn register_batch(&self, canonicals: &[String]) -> Result<Vec<i32>, JsonRegisterError> {
+        self.queries_executed.fetch_add(1, Ordering::Relaxed);
+
+        let mut entries = self.entries.lock().expect("entries mutex poisoned");
+        let mut by_id = self.by_id.lock().expect("by_id mutex poisoned");
+        let mut ids = Vec::with_capacity(canonicals.len());
+        for canonical in canonicals {
+            let id = if let Some(&id) = entries.get(canonical) {
+                id
+            } else {
+                let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                entries.insert(canonical.clone(), id);
+                by_id.insert(id, canonical.clone());
+                id
+            };
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    async fn lookup_id(&self, canonical: &str) -> Result<Option<i32>, JsonRegisterError> {
+        self.queries_executed.fetch_add(1, Ordering::Relaxed);
+        Ok(self
+            .entries
+            .lock()
+            .expect("entries mutex poisoned")
+            .get(canonical)
+            .copied())
+    }
+
+    fn queries_executed(&self) -> u64 {
+        self.queries_executed.load(Ordering::Relaxed)
+    }
+
+    async fn get_object(&self, id: i32) -> Result<Option<String>, JsonRegisterError> {
+        self.queries_executed.fetch_add(1, Ordering::Relaxed);
+        Ok(self
+            .by_id
+            .lock()
+            .expect("by_id mutex poisoned")
+            .get(&id)
+            .cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_register_one_is_idempotent() {
+        let backend = InMemoryBackend::new();
+        let id1 = backend.register_one("{\"a\":1}").await.unwrap();
+        let id2 = backend.register_one("{\"a\":1}").await.unwrap();
+        assert_eq!(id1, id2);
+        assert_eq!(backend.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_register_batch_preserves_order_with_duplicates() {
+        let backend = InMemoryBackend::new();
+        let first = backend.register_one("{\"a\":1}").await.unwrap();
+
+        let batch = vec![
+            "{\"a\":1}".to_string(),
+            "{\"b\":2}".to_string(),
+            "{\"a\":1}".to_string(),
+        ];
+        let ids = backend.register_batch(&batch).await.unwrap();
+
+        assert_eq!(ids[0], first);
+        assert_eq!(ids[0], ids[2]);
+        assert_ne!(ids[0], ids[1]);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_id_does_not_insert() {
+        let backend = InMemoryBackend::new();
+        assert_eq!(backend.lookup_id("{\"a\":1}").await.unwrap(), None);
+        assert!(backend.is_empty());
+
+        let id = backend.register_one("{\"a\":1}").await.unwrap();
+        assert_eq!(backend.lookup_id("{\"a\":1}").await.unwrap(), Some(id));
+    }
+
+    #[tokio::test]
+    async fn test_get_object_returns_registered_canonical_string() {
+        let backend = InMemoryBackend::new();
+        let id = backend.register_one("{\"a\":1}").await.unwrap();
+        assert_eq!(
+            backend.get_object(id).await.unwrap(),
+            Some("{\"a\":1}".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_object_returns_none_for_unknown_id() {
+        let backend = InMemoryBackend::new();
+        assert_eq!(backend.get_object(999).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,248 @@
+use prometheus::{Encoder, Gauge, IntCounter, IntGauge, Registry, TextEncoder};
+
+use crate::errors::JsonRegisterError;
+use crate::TelemetryMetrics;
+
+/// Prometheus collectors for a `Register`'s telemetry.
+///
+/// `Metrics` registers a fixed set of gauges/counters on a `prometheus::Registry`
+/// (one provided by the caller, or a fresh default) and refreshes their values
+/// from a `TelemetryMetrics` snapshot on demand via [`Metrics::update`]. The
+/// atomics on `Register`/`Cache`/`Db` remain the source of truth; these
+/// collectors are a read-only projection of them, so calling `update` repeatedly
+/// never double-counts.
+pub struct Metrics {
+    registry: Registry,
+    cache_hits: IntCounter,
+    cache_misses: IntCounter,
+    cache_hit_rate: Gauge,
+    cache_size: IntGauge,
+    cache_capacity: IntGauge,
+    cache_evictions: IntCounter,
+    pool_size: IntGauge,
+    idle_connections: IntGauge,
+    active_connections: IntGauge,
+    db_queries_total: IntCounter,
+    db_query_errors: IntCounter,
+    register_single_calls: IntCounter,
+    register_batch_calls: IntCounter,
+    total_objects_registered: IntCounter,
+}
+
+impl Metrics {
+    /// Creates a new set of metrics and registers them on `registry`.
+    ///
+    /// If `registry` is `None`, a fresh default `prometheus::Registry` is created.
+    /// `prefix` namespaces every metric name (e.g. `json_register`) so multiple
+    /// `Register` instances can share a process without name collisions, as long
+    /// as each is given a distinct prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JsonRegisterError::Configuration` if a metric with the same name
+    /// is already registered on `registry`.
+    pub fn new(registry: Option<Registry>, prefix: &str) -> Result<Self, JsonRegisterError> {
+        let registry = registry.unwrap_or_default();
+
+        let name = |suffix: &str| format!("{prefix}_{suffix}");
+        let register_counter = |registry: &Registry, suffix: &str, help: &str| {
+            let counter = IntCounter::new(name(suffix), help)
+                .map_err(|e| JsonRegisterError::Configuration(e.to_string()))?;
+            registry
+                .register(Box::new(counter.clone()))
+                .map_err(|e| JsonRegisterError::Configuration(e.to_string()))?;
+            Ok::<_, JsonRegisterError>(counter)
+        };
+        let register_int_gauge = |registry: &Registry, suffix: &str, help: &str| {
+            let gauge = IntGauge::new(name(suffix), help)
+                .map_err(|e| JsonRegisterError::Configuration(e.to_string()))?;
+            registry
+                .register(Box::new(gauge.clone()))
+                .map_err(|e| JsonRegisterError::Configuration(e.to_string()))?;
+            Ok::<_, JsonRegisterError>(gauge)
+        };
+        let register_gauge = |registry: &Registry, suffix: &str, help: &str| {
+            let gauge = Gauge::new(name(suffix), help)
+                .map_err(|e| JsonRegisterError::Configuration(e.to_string()))?;
+            registry
+                .register(Box::new(gauge.clone()))
+                .map_err(|e| JsonRegisterError::Configuration(e.to_string()))?;
+            Ok::<_, JsonRegisterError>(gauge)
+        };
+
+        Ok(Self {
+            cache_hits: register_counter(&registry, "cache_hits_total", "Total cache hits")?,
+            cache_misses: register_counter(&registry, "cache_misses_total", "Total cache misses")?,
+            cache_hit_rate: register_gauge(
+                &registry,
+                "cache_hit_rate",
+                "Cache hit rate as a percentage",
+            )?,
+            cache_size: register_int_gauge(&registry, "cache_size", "Current cache entry count")?,
+            cache_capacity: register_int_gauge(&registry, "cache_capacity", "Cache capacity")?,
+            cache_evictions: register_counter(
+                &registry,
+                "cache_evictions_total",
+                "Total cache evictions",
+            )?,
+            pool_size: register_int_gauge(&registry, "pool_size", "Connection pool size")?,
+            idle_connections: register_int_gauge(
+                &registry,
+                "pool_idle_connections",
+                "Idle connections in the pool",
+            )?,
+            active_connections: register_int_gauge(
+                &registry,
+                "pool_active_connections",
+                "Active (in-use) connections in the pool",
+            )?,
+            db_queries_total: register_counter(
+                &registry,
+                "db_queries_total",
+                "Total database queries executed",
+            )?,
+            db_query_errors: register_counter(
+                &registry,
+                "db_query_errors_total",
+                "Total database query errors",
+            )?,
+            register_single_calls: register_counter(
+                &registry,
+                "register_single_calls_total",
+                "Total calls to register_object",
+            )?,
+            register_batch_calls: register_counter(
+                &registry,
+                "register_batch_calls_total",
+                "Total calls to register_batch_objects",
+            )?,
+            total_objects_registered: register_counter(
+                &registry,
+                "total_objects_registered_total",
+                "Total objects registered across single and batch calls",
+            )?,
+            registry,
+        })
+    }
+
+    /// Refreshes every gauge/counter from a telemetry snapshot.
+    ///
+    /// Counters are set (not incremented) to the snapshot's cumulative totals,
+    /// so calling this repeatedly reflects the current state without
+    /// double-counting.
+    pub fn update(&self, snapshot: &TelemetryMetrics) {
+        set_counter(&self.cache_hits, snapshot.cache_hits);
+        set_counter(&self.cache_misses, snapshot.cache_misses);
+        self.cache_hit_rate.set(snapshot.cache_hit_rate);
+        self.cache_size.set(snapshot.cache_size as i64);
+        self.cache_capacity.set(snapshot.cache_capacity as i64);
+        set_counter(&self.cache_evictions, snapshot.cache_evictions);
+        self.pool_size.set(snapshot.pool_size as i64);
+        self.idle_connections.set(snapshot.idle_connections as i64);
+        self.active_connections
+            .set(snapshot.active_connections as i64);
+        set_counter(&self.db_queries_total, snapshot.db_queries_total);
+        set_counter(&self.db_query_errors, snapshot.db_query_errors);
+        set_counter(&self.register_single_calls, snapshot.register_single_calls);
+        set_counter(&self.register_batch_calls, snapshot.register_batch_calls);
+        set_counter(
+            &self.total_objects_registered,
+            snapshot.total_objects_registered,
+        );
+    }
+
+    /// Returns the `Registry` these metrics are registered on.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Encodes all metrics on the underlying registry in Prometheus text
+    /// exposition format, suitable for serving on a `/metrics` endpoint.
+    pub fn encode(&self) -> Result<String, JsonRegisterError> {
+        let encoder = TextEncoder::new();
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        encoder
+            .encode(&metric_families, &mut buffer)
+            .map_err(|e| JsonRegisterError::Configuration(e.to_string()))?;
+        String::from_utf8(buffer).map_err(|e| JsonRegisterError::Configuration(e.to_string()))
+    }
+}
+
+/// `IntCounter` only exposes `inc`/`inc_by`, so reconcile it with a cumulative
+/// total by adding the delta since the last observed value.
+fn set_counter(counter: &IntCounter, total: u64) {
+    let current = counter.get() as u64;
+    if total > current {
+        counter.inc_by(total - current);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_snapshot() -> TelemetryMetrics {
+        TelemetryMetrics {
+            cache_hits: 10,
+            cache_misses: 5,
+            cache_hit_rate: 66.6,
+            cache_size: 3,
+            cache_capacity: 100,
+            cache_evictions: 0,
+            pool_size: 5,
+            idle_connections: 4,
+            active_connections: 1,
+            is_closed: false,
+            db_queries_total: 7,
+            db_query_errors: 1,
+            register_single_calls: 6,
+            register_batch_calls: 1,
+            total_objects_registered: 7,
+        }
+    }
+
+    #[test]
+    fn test_update_reflects_snapshot_values() {
+        let metrics = Metrics::new(None, "json_register_test").unwrap();
+        metrics.update(&sample_snapshot());
+
+        assert_eq!(metrics.cache_hits.get(), 10);
+        assert_eq!(metrics.cache_misses.get(), 5);
+        assert_eq!(metrics.cache_size.get(), 3);
+        assert_eq!(metrics.db_query_errors.get(), 1);
+        assert_eq!(metrics.register_single_calls.get(), 6);
+        assert_eq!(metrics.register_batch_calls.get(), 1);
+        assert_eq!(metrics.total_objects_registered.get(), 7);
+    }
+
+    #[test]
+    fn test_update_does_not_double_count_on_repeat() {
+        let metrics = Metrics::new(None, "json_register_test2").unwrap();
+        let snapshot = sample_snapshot();
+
+        metrics.update(&snapshot);
+        metrics.update(&snapshot);
+
+        assert_eq!(metrics.cache_hits.get(), snapshot.cache_hits as i64);
+        assert_eq!(metrics.db_queries_total.get(), snapshot.db_queries_total as i64);
+    }
+
+    #[test]
+    fn test_encode_contains_prefixed_metric_names() {
+        let metrics = Metrics::new(None, "json_register_test3").unwrap();
+        metrics.update(&sample_snapshot());
+
+        let text = metrics.encode().unwrap();
+        assert!(text.contains("json_register_test3_cache_hits_total"));
+        assert!(text.contains("json_register_test3_db_query_errors_total"));
+    }
+
+    #[test]
+    fn test_duplicate_prefix_on_same_registry_errors() {
+        let registry = Registry::new();
+        let _first = Metrics::new(Some(registry.clone()), "dup").unwrap();
+        let second = Metrics::new(Some(registry), "dup");
+        assert!(second.is_err());
+    }
+}
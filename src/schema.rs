@@ -0,0 +1,153 @@
+use crate::db::DedupMode;
+
+/// Name of the small metadata table tracking which schema version has been
+/// applied to each target table.
+const MIGRATIONS_TABLE: &str = "_json_register_migrations";
+
+/// Name of the shared append-only event log table (see
+/// [`crate::events::Event`]). One table serves every `Register`'s event
+/// stream, distinguished by its `table_name` column.
+pub(crate) const EVENTS_TABLE: &str = "_json_register_events";
+
+/// The current schema version. Bump this (and extend [`ddl_statements`])
+/// whenever the generated DDL changes in a way existing installations need
+/// to roll forward to.
+const CURRENT_VERSION: i32 = 1;
+
+/// Generates the DDL statements needed to create (or migrate) `table_name`
+/// for the configured dedup mode, in application order.
+///
+/// Every statement is idempotent — `CREATE TABLE IF NOT EXISTS` and an
+/// `ON CONFLICT ... DO UPDATE` version bump — so calling this repeatedly
+/// against an already-migrated table is a no-op. Identifiers are assumed to
+/// have already been validated by [`crate::db::validate_sql_identifier`].
+pub(crate) fn ddl_statements(
+    table_name: &str,
+    id_column: &str,
+    jsonb_column: &str,
+    dedup_mode: &DedupMode,
+    payload_is_binary: bool,
+    event_log_enabled: bool,
+) -> Vec<String> {
+    let mut statements = vec![format!(
+        "CREATE TABLE IF NOT EXISTS {MIGRATIONS_TABLE} (\
+             table_name TEXT PRIMARY KEY, \
+             version INT NOT NULL\
+         )"
+    )];
+
+    // Encrypted and/or compressed payloads aren't valid JSON, so they need a
+    // BYTEA column rather than JSONB.
+    let payload_type = if payload_is_binary { "BYTEA" } else { "JSONB" };
+
+    statements.push(match dedup_mode {
+        DedupMode::JsonbUnique => format!(
+            "CREATE TABLE IF NOT EXISTS {table_name} (\
+                 {id_column} SERIAL PRIMARY KEY, \
+                 {jsonb_column} {payload_type} UNIQUE NOT NULL\
+             )"
+        ),
+        DedupMode::HashColumn { hash_column } => format!(
+            "CREATE TABLE IF NOT EXISTS {table_name} (\
+                 {id_column} SERIAL PRIMARY KEY, \
+                 {hash_column} TEXT UNIQUE NOT NULL, \
+                 {jsonb_column} {payload_type} NOT NULL\
+             )"
+        ),
+    });
+
+    statements.push(format!(
+        "INSERT INTO {MIGRATIONS_TABLE} (table_name, version) VALUES ('{table_name}', {CURRENT_VERSION}) \
+         ON CONFLICT (table_name) DO UPDATE SET version = EXCLUDED.version \
+         WHERE {MIGRATIONS_TABLE}.version < EXCLUDED.version"
+    ));
+
+    if event_log_enabled {
+        statements.push(format!(
+            "CREATE TABLE IF NOT EXISTS {EVENTS_TABLE} (\
+                 seq BIGSERIAL PRIMARY KEY, \
+                 table_name TEXT NOT NULL, \
+                 id INTEGER NOT NULL, \
+                 digest TEXT NOT NULL, \
+                 canonical TEXT NOT NULL, \
+                 registered_at BIGINT NOT NULL, \
+                 UNIQUE (table_name, id)\
+             )"
+        ));
+        statements.push(format!(
+            "CREATE INDEX IF NOT EXISTS {EVENTS_TABLE}_table_seq_idx ON {EVENTS_TABLE} (table_name, seq)"
+        ));
+    }
+
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ddl_statements_jsonb_unique_includes_unique_constraint() {
+        let statements =
+            ddl_statements("objects", "id", "data", &DedupMode::JsonbUnique, false, false);
+        assert_eq!(statements.len(), 3);
+        assert!(statements[1].contains("JSONB UNIQUE NOT NULL"));
+        assert!(statements[2].contains("'objects'"));
+    }
+
+    #[test]
+    fn test_ddl_statements_hash_column_includes_hash_unique_constraint() {
+        let statements = ddl_statements(
+            "objects",
+            "id",
+            "data",
+            &DedupMode::HashColumn {
+                hash_column: "content_hash".to_string(),
+            },
+            false,
+            false,
+        );
+        assert_eq!(statements.len(), 3);
+        assert!(statements[1].contains("content_hash TEXT UNIQUE NOT NULL"));
+        assert!(statements[1].contains("data JSONB NOT NULL"));
+    }
+
+    #[test]
+    fn test_ddl_statements_always_creates_migrations_table_first() {
+        let statements =
+            ddl_statements("objects", "id", "data", &DedupMode::JsonbUnique, false, false);
+        assert!(statements[0].contains(MIGRATIONS_TABLE));
+    }
+
+    #[test]
+    fn test_ddl_statements_encrypted_payload_uses_bytea() {
+        let statements = ddl_statements(
+            "objects",
+            "id",
+            "data",
+            &DedupMode::HashColumn {
+                hash_column: "content_hash".to_string(),
+            },
+            true,
+            false,
+        );
+        assert!(statements[1].contains("data BYTEA NOT NULL"));
+    }
+
+    #[test]
+    fn test_ddl_statements_event_log_adds_events_table_and_index() {
+        let statements =
+            ddl_statements("objects", "id", "data", &DedupMode::JsonbUnique, false, true);
+        assert_eq!(statements.len(), 5);
+        assert!(statements[3].contains(EVENTS_TABLE));
+        assert!(statements[3].contains("UNIQUE (table_name, id)"));
+        assert!(statements[4].contains("CREATE INDEX"));
+    }
+
+    #[test]
+    fn test_ddl_statements_event_log_disabled_by_default() {
+        let statements =
+            ddl_statements("objects", "id", "data", &DedupMode::JsonbUnique, false, false);
+        assert!(!statements.iter().any(|s| s.contains(EVENTS_TABLE)));
+    }
+}
@@ -0,0 +1,177 @@
+use std::io::{Read, Write};
+
+use serde_json::{Deserializer, Value};
+
+use crate::canonicalise::{canonicalise_with, CanonicalForm};
+use crate::errors::JsonRegisterError;
+
+/// One record produced by [`CanonicalisedStream`]: the canonicalised value
+/// and the byte offset, within the input stream, immediately after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalRecord {
+    /// The canonicalised JSON string for this record.
+    pub canonical: String,
+    /// Byte offset of the end of this record in the input stream, useful
+    /// for pinpointing where a later record's parse error occurred.
+    pub offset: u64,
+}
+
+/// Canonicalises a newline- or whitespace-separated stream of top-level JSON
+/// values (NDJSON or concatenated JSON) one record at a time, without
+/// buffering the whole input. Each top-level value is canonicalised under
+/// the same [`CanonicalForm`] as the single-value API.
+///
+/// Construct via [`canonicalise_stream`].
+pub struct CanonicalisedStream<R: Read> {
+    values: serde_json::StreamDeserializer<'static, serde_json::de::IoRead<R>, Value>,
+    form: CanonicalForm,
+}
+
+impl<R: Read> Iterator for CanonicalisedStream<R> {
+    type Item = Result<CanonicalRecord, JsonRegisterError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = match self.values.next()? {
+            Ok(value) => value,
+            Err(err) => return Some(Err(JsonRegisterError::SerdeError(err))),
+        };
+        let offset = self.values.byte_offset() as u64;
+        Some(canonicalise_with(&value, self.form).map(|canonical| CanonicalRecord {
+            canonical,
+            offset,
+        }))
+    }
+}
+
+/// Streams `reader` as a sequence of top-level JSON values and canonicalises
+/// each one under `form`, yielding one `Result` per record as soon as it's
+/// parsed. Handles both newline-separated NDJSON and whitespace-separated
+/// concatenated JSON (e.g. `{"x":39} {"x":40}\n{"x":41}`).
+pub fn canonicalise_stream<R: Read>(reader: R, form: CanonicalForm) -> CanonicalisedStream<R> {
+    CanonicalisedStream {
+        values: Deserializer::from_reader(reader).into_iter::<Value>(),
+        form,
+    }
+}
+
+/// Canonicalises every top-level value in `reader` under `form` and writes
+/// one canonical JSON document per line to `writer`. Returns the number of
+/// records written.
+///
+/// # Errors
+///
+/// Returns `JsonRegisterError::SerdeError` if a record fails to parse,
+/// whatever `canonicalise_with` returns if a record fails to canonicalise
+/// under `form`, or `JsonRegisterError::RuntimeError` if writing to
+/// `writer` fails.
+pub fn canonicalise_ndjson<R: Read, W: Write>(
+    reader: R,
+    writer: &mut W,
+    form: CanonicalForm,
+) -> Result<usize, JsonRegisterError> {
+    let mut count = 0;
+    for record in canonicalise_stream(reader, form) {
+        let record = record?;
+        writeln!(writer, "{}", record.canonical)
+            .map_err(|err| JsonRegisterError::RuntimeError(err.to_string()))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonicalise_stream_newline_separated() {
+        let input = b"{\"b\":2,\"a\":1}\n{\"z\":1}\n";
+        let records: Vec<_> = canonicalise_stream(&input[..], CanonicalForm::Default)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].canonical, r#"{"a":1,"b":2}"#);
+        assert_eq!(records[1].canonical, r#"{"z":1}"#);
+    }
+
+    #[test]
+    fn test_canonicalise_stream_whitespace_separated() {
+        let input = b"{\"x\":39} {\"x\":40}\n{\"x\":41}";
+        let records: Vec<_> = canonicalise_stream(&input[..], CanonicalForm::Default)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let canonicals: Vec<_> = records.into_iter().map(|r| r.canonical).collect();
+        assert_eq!(canonicals, vec![r#"{"x":39}"#, r#"{"x":40}"#, r#"{"x":41}"#]);
+    }
+
+    #[test]
+    fn test_canonicalise_stream_tracks_increasing_byte_offsets() {
+        let input = b"{\"a\":1}\n{\"b\":2}\n";
+        let records: Vec<_> = canonicalise_stream(&input[..], CanonicalForm::Default)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(records[0].offset < records[1].offset);
+        // The offset is taken right after the second value's closing brace;
+        // the trailing newline isn't part of the record.
+        assert_eq!(records[1].offset as usize, input.len() - 1);
+    }
+
+    #[test]
+    fn test_canonicalise_stream_reports_parse_error_without_buffering_rest() {
+        let input = b"{\"a\":1}\nnot json\n{\"b\":2}\n";
+        let mut stream = canonicalise_stream(&input[..], CanonicalForm::Default);
+        assert!(stream.next().unwrap().is_ok());
+        assert!(stream.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_canonicalise_stream_uses_selected_profile() {
+        let input = r#"{"z": 1e10, "a": null}"#;
+        let records: Vec<_> = canonicalise_stream(input.as_bytes(), CanonicalForm::Jcs)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records[0].canonical, r#"{"a":null,"z":10000000000}"#);
+    }
+
+    #[test]
+    fn test_canonicalise_stream_empty_input_yields_no_records() {
+        let records: Vec<_> = canonicalise_stream(&b""[..], CanonicalForm::Default)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn test_canonicalise_ndjson_writes_one_line_per_record() {
+        let input = b"{\"b\":2,\"a\":1}\n{\"z\":1}\n";
+        let mut output = Vec::new();
+        let count = canonicalise_ndjson(&input[..], &mut output, CanonicalForm::Default).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"a\":1,\"b\":2}\n{\"z\":1}\n"
+        );
+    }
+
+    #[test]
+    fn test_canonicalise_ndjson_stops_at_first_parse_error() {
+        let input = b"{\"a\":1}\nnot json\n";
+        let mut output = Vec::new();
+        let result = canonicalise_ndjson(&input[..], &mut output, CanonicalForm::Default);
+        assert!(result.is_err());
+        assert_eq!(String::from_utf8(output).unwrap(), "{\"a\":1}\n");
+    }
+
+    #[test]
+    fn test_canonicalise_stream_matches_single_value_api() {
+        let obj = json!({"b": 2, "a": 1});
+        let input = serde_json::to_vec(&obj).unwrap();
+        let mut records = canonicalise_stream(&input[..], CanonicalForm::Default);
+        let record = records.next().unwrap().unwrap();
+        assert_eq!(
+            record.canonical,
+            crate::canonicalise::canonicalise(&obj).unwrap()
+        );
+    }
+}
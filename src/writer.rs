@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::backend::map_register_err;
+use crate::db::RegisterError;
+use crate::errors::JsonRegisterError;
+use crate::Db;
+
+/// Configures the background buffered writer's coalescing behaviour.
+#[derive(Debug, Clone, Copy)]
+pub struct BufferedWriterConfig {
+    /// Flush as soon as this many requests are buffered.
+    pub buffer_size: usize,
+    /// Flush at least this often, even if `buffer_size` hasn't been reached.
+    pub max_latency: Duration,
+    /// Capacity of the `mpsc` channel submitters send requests over.
+    pub channel_capacity: usize,
+}
+
+impl Default for BufferedWriterConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: 100,
+            max_latency: Duration::from_millis(50),
+            channel_capacity: 1024,
+        }
+    }
+}
+
+struct PendingRequest {
+    json_str: String,
+    responder: oneshot::Sender<Result<i32, Arc<RegisterError>>>,
+}
+
+/// A cheaply cloneable handle for submitting single-object registrations to a
+/// background buffered writer.
+///
+/// Dropping every clone of the handle closes the submission channel, which
+/// causes the writer task to drain and flush any buffered requests, then
+/// exit.
+#[derive(Clone)]
+pub struct BufferedWriterHandle {
+    sender: mpsc::Sender<PendingRequest>,
+}
+
+impl BufferedWriterHandle {
+    /// Submits a single canonicalised JSON string for registration.
+    ///
+    /// Resolves once the batch it was coalesced into has been flushed,
+    /// yielding the same `i32` id a direct `Db::register_object` call would
+    /// have returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns `JsonRegisterError::RuntimeError` if the writer task has
+    /// already shut down, or if the batch containing this request failed.
+    pub async fn register_object(&self, json_str: String) -> Result<i32, JsonRegisterError> {
+        let (responder, receiver) = oneshot::channel();
+        self.sender
+            .send(PendingRequest {
+                json_str,
+                responder,
+            })
+            .await
+            .map_err(|_| {
+                JsonRegisterError::RuntimeError("buffered writer task has shut down".into())
+            })?;
+
+        receiver
+            .await
+            .map_err(|_| {
+                JsonRegisterError::RuntimeError(
+                    "buffered writer task dropped the response channel".into(),
+                )
+            })?
+            .map_err(|e| map_register_err(&e))
+    }
+}
+
+/// Spawns a background task that coalesces individual `register_object`
+/// requests into `Db::register_batch_objects` calls.
+///
+/// The task flushes whenever either `config.buffer_size` requests are
+/// buffered or `config.max_latency` elapses since the oldest buffered
+/// request, whichever comes first. Each submitter gets back its resolved id
+/// via a `oneshot` responder once its batch completes; a failed batch
+/// propagates the same error to every responder in that batch. `queries_executed`
+/// and `query_errors` on `db` are updated exactly as they would be for a
+/// direct `register_batch_objects` call, since this is implemented on top of it.
+///
+/// # Returns
+///
+/// A `(BufferedWriterHandle, JoinHandle<()>)` pair. Drop every clone of the
+/// handle to trigger a graceful shutdown: the task drains its channel,
+/// flushes any remaining requests, and exits. Await the `JoinHandle` to wait
+/// for that shutdown to complete.
+pub fn spawn_buffered_writer(
+    db: Arc<Db>,
+    config: BufferedWriterConfig,
+) -> (BufferedWriterHandle, JoinHandle<()>) {
+    let (sender, mut receiver) = mpsc::channel::<PendingRequest>(config.channel_capacity);
+
+    let join_handle = tokio::spawn(async move {
+        let mut pending: Vec<PendingRequest> = Vec::with_capacity(config.buffer_size);
+
+        // Armed once per batch (when the first request of a new batch
+        // arrives) rather than recreated on every loop iteration, so a
+        // steady stream of sub-`max_latency`-interval arrivals can't push
+        // the flush deadline out indefinitely.
+        let sleep = tokio::time::sleep(config.max_latency);
+        tokio::pin!(sleep);
+
+        loop {
+            tokio::select! {
+                received = receiver.recv() => {
+                    match received {
+                        Some(request) => {
+                            let starting_new_batch = pending.is_empty();
+                            pending.push(request);
+                            if starting_new_batch {
+                                sleep.as_mut().reset(tokio::time::Instant::now() + config.max_latency);
+                            }
+                            if pending.len() >= config.buffer_size {
+                                flush(&db, std::mem::take(&mut pending)).await;
+                            }
+                        }
+                        None => {
+                            flush(&db, std::mem::take(&mut pending)).await;
+                            break;
+                        }
+                    }
+                }
+                _ = &mut sleep => {
+                    if !pending.is_empty() {
+                        flush(&db, std::mem::take(&mut pending)).await;
+                    }
+                    sleep.as_mut().reset(tokio::time::Instant::now() + config.max_latency);
+                }
+            }
+        }
+    });
+
+    (BufferedWriterHandle { sender }, join_handle)
+}
+
+/// Flushes a buffered batch: issues one `register_batch_objects` call and
+/// resolves every responder with the matching id, or with the same error if
+/// the batch failed.
+///
+/// Coalesced requests aren't deduplicated against each other before they
+/// land here (unlike `Register::register_batch_objects`, this writer has no
+/// cache to check and no canonicalisation step), so the same JSON string can
+/// appear more than once in `pending`. It's grouped by value and sent to
+/// `register_batch_objects` only once per distinct value, the same way
+/// `Register::register_batch_objects` dedups before calling its backend,
+/// so every responder still gets the id matching its own request.
+async fn flush(db: &Db, pending: Vec<PendingRequest>) {
+    if pending.is_empty() {
+        return;
+    }
+
+    let mut positions: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut unique_json_strs: Vec<String> = Vec::new();
+    for (i, request) in pending.iter().enumerate() {
+        positions
+            .entry(request.json_str.as_str())
+            .or_insert_with(|| {
+                unique_json_strs.push(request.json_str.clone());
+                Vec::new()
+            })
+            .push(i);
+    }
+
+    match db.register_batch_objects(&unique_json_strs).await {
+        Ok(ids) => {
+            let mut ids_by_position: Vec<Option<i32>> = vec![None; pending.len()];
+            for (json_str, id) in unique_json_strs.iter().zip(ids) {
+                for &pos in &positions[json_str.as_str()] {
+                    ids_by_position[pos] = Some(id);
+                }
+            }
+
+            for (request, id) in pending.into_iter().zip(ids_by_position) {
+                let _ = request.responder.send(Ok(
+                    id.expect("every position resolved by the batch call above")
+                ));
+            }
+        }
+        Err(e) => {
+            let shared_err = Arc::new(e);
+            for request in pending {
+                let _ = request.responder.send(Err(shared_err.clone()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Db;
+
+    // Port 1 is almost never listening and refuses connections immediately,
+    // so `register_batch_objects` fails fast and deterministically without a
+    // real Postgres instance. `Db::new` itself succeeds regardless, since
+    // pool construction is lazy and doesn't connect until a query is issued.
+    async fn unreachable_db() -> Arc<Db> {
+        Arc::new(
+            Db::new(
+                "postgresql://127.0.0.1:1/test",
+                "objects",
+                "id",
+                "data",
+                1,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                false,
+                None,
+                None,
+            )
+            .await
+            .expect("pool creation is lazy and should succeed without a reachable server"),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_buffer_size_triggers_flush_before_max_latency() {
+        let db = unreachable_db().await;
+        let config = BufferedWriterConfig {
+            buffer_size: 2,
+            max_latency: Duration::from_secs(30),
+            channel_capacity: 16,
+        };
+        let (handle, _join) = spawn_buffered_writer(db, config);
+
+        let h1 = handle.clone();
+        let h2 = handle.clone();
+        let (r1, r2) = tokio::time::timeout(Duration::from_secs(5), async move {
+            tokio::join!(
+                h1.register_object("{}".to_string()),
+                h2.register_object("{}".to_string()),
+            )
+        })
+        .await
+        .expect("buffer_size flush should fire long before the 30s max_latency");
+
+        assert!(r1.is_err());
+        assert!(r2.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_max_latency_triggers_flush_before_buffer_size() {
+        let db = unreachable_db().await;
+        let config = BufferedWriterConfig {
+            buffer_size: 1000,
+            max_latency: Duration::from_millis(50),
+            channel_capacity: 16,
+        };
+        let (handle, _join) = spawn_buffered_writer(db, config);
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            handle.register_object("{}".to_string()),
+        )
+        .await
+        .expect("max_latency flush should fire even though buffer_size is never reached");
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_propagates_same_error_to_all_responders() {
+        let db = unreachable_db().await;
+        let config = BufferedWriterConfig {
+            buffer_size: 3,
+            max_latency: Duration::from_secs(30),
+            channel_capacity: 16,
+        };
+        let (handle, _join) = spawn_buffered_writer(db, config);
+
+        let h1 = handle.clone();
+        let h2 = handle.clone();
+        let h3 = handle.clone();
+        let (r1, r2, r3) = tokio::time::timeout(Duration::from_secs(5), async move {
+            tokio::join!(
+                h1.register_object("{}".to_string()),
+                h2.register_object("{}".to_string()),
+                h3.register_object("{}".to_string()),
+            )
+        })
+        .await
+        .expect("batch should flush once buffer_size is reached");
+
+        let e1 = r1.unwrap_err();
+        let e2 = r2.unwrap_err();
+        let e3 = r3.unwrap_err();
+        assert_eq!(e1.to_string(), e2.to_string());
+        assert_eq!(e2.to_string(), e3.to_string());
+    }
+}
@@ -0,0 +1,110 @@
+use json_register::{Cache, InMemoryBackend, Register};
+use serde_json::json;
+use std::collections::HashSet;
+
+/// Creates a `Register` backed by a fresh `InMemoryBackend` for testing.
+///
+/// Unlike the PostgreSQL-backed tests in `integration_test.rs`, these run
+/// with no external dependencies and are never `#[ignore]`d.
+fn create_register() -> Register<InMemoryBackend> {
+    Register::with_backend(InMemoryBackend::new(), Cache::new(100))
+}
+
+#[tokio::test]
+async fn test_register_object() {
+    let register = create_register();
+    let obj = json!({"name": "Alice", "age": 30});
+
+    let id1 = register.register_object(&obj).await.unwrap();
+    let id2 = register.register_object(&obj).await.unwrap();
+
+    assert_eq!(id1, id2);
+}
+
+#[tokio::test]
+async fn test_register_batch_objects() {
+    let register = create_register();
+    let objects = vec![
+        json!({"name": "Alice"}),
+        json!({"name": "Bob"}),
+        json!({"name": "Carol"}),
+    ];
+
+    let ids = register.register_batch_objects(&objects).await.unwrap();
+
+    assert_eq!(ids.len(), 3);
+    let unique_ids: HashSet<_> = ids.iter().collect();
+    assert_eq!(unique_ids.len(), 3);
+}
+
+#[tokio::test]
+async fn test_batch_order_preserved_all_new() {
+    let register = create_register();
+
+    let objects = vec![
+        json!({"test": "batch_order_1", "index": 0}),
+        json!({"test": "batch_order_2", "index": 1}),
+        json!({"test": "batch_order_3", "index": 2}),
+        json!({"test": "batch_order_4", "index": 3}),
+    ];
+
+    let batch_ids = register.register_batch_objects(&objects).await.unwrap();
+    assert_eq!(batch_ids.len(), 4);
+
+    for (i, obj) in objects.iter().enumerate() {
+        let individual_id = register.register_object(obj).await.unwrap();
+        assert_eq!(
+            batch_ids[i], individual_id,
+            "Object at index {} should have matching ID",
+            i
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_batch_different_key_orders_same_ids() {
+    let register = create_register();
+
+    let batch1 = vec![
+        json!({"name": "Alice", "age": 30}),
+        json!({"name": "Bob", "age": 25}),
+    ];
+    let ids1 = register.register_batch_objects(&batch1).await.unwrap();
+
+    let batch2 = vec![
+        json!({"age": 30, "name": "Alice"}),
+        json!({"age": 25, "name": "Bob"}),
+    ];
+    let ids2 = register.register_batch_objects(&batch2).await.unwrap();
+
+    assert_eq!(ids1, ids2);
+}
+
+#[tokio::test]
+async fn test_batch_duplicate_objects_within_batch_dedup() {
+    let register = create_register();
+
+    let alice = json!({"name": "Alice"});
+    let bob = json!({"name": "Bob"});
+    let objects = vec![alice.clone(), bob.clone(), alice.clone(), bob.clone()];
+
+    let ids = register.register_batch_objects(&objects).await.unwrap();
+
+    assert_eq!(ids.len(), 4);
+    assert_eq!(ids[0], ids[2]);
+    assert_eq!(ids[1], ids[3]);
+
+    let unique_ids: HashSet<_> = ids.iter().collect();
+    assert_eq!(unique_ids.len(), 2);
+}
+
+#[tokio::test]
+async fn test_lookup_object_does_not_register() {
+    let register = create_register();
+    let obj = json!({"name": "Dave"});
+
+    assert_eq!(register.lookup_object(&obj).await.unwrap(), None);
+
+    let id = register.register_object(&obj).await.unwrap();
+    assert_eq!(register.lookup_object(&obj).await.unwrap(), Some(id));
+}
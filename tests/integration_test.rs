@@ -1,7 +1,11 @@
 use json_register::{build_connection_string, Register};
+#[cfg(feature = "buffered-writer")]
+use json_register::{spawn_buffered_writer, BufferedWriterConfig, Db};
 use serde_json::json;
 use std::collections::HashSet;
 use std::env;
+#[cfg(feature = "buffered-writer")]
+use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Retrieves database configuration from environment variables.
@@ -71,11 +75,68 @@ async fn create_register(suffix: &str) -> Register {
         .await
         .expect("Failed to create table");
 
-    Register::new(
-        &conn_str, &table, &id_col, &json_col, pool_size, cache_size,
-        None, // acquire_timeout_secs
-        None, // idle_timeout_secs
-        None, // max_lifetime_secs
+    Register::builder(&conn_str, &table, &id_col, &json_col)
+        .pool_size(pool_size)
+        .cache_size(cache_size)
+        .build()
+        .await
+        .expect("Failed to connect to DB")
+}
+
+/// Creates a `Db` instance for testing, for cases (like the buffered writer)
+/// that operate on `Db` directly rather than through `Register`.
+///
+/// Mirrors `create_register`'s table setup so both helpers can exercise the
+/// same underlying schema.
+#[cfg(feature = "buffered-writer")]
+async fn create_db(suffix: &str) -> Db {
+    let (db_name, host, port, user, password, _cache_size, base_table, id_col, json_col, pool_size) =
+        get_config();
+    let table = format!("{}_{}", base_table, suffix);
+    let port_num: u16 = port.parse().expect("Invalid port number");
+    let conn_str = build_connection_string(&user, &password, &host, port_num, &db_name);
+
+    let (client, connection) = tokio_postgres::connect(&conn_str, tokio_postgres::NoTls)
+        .await
+        .expect("Failed to connect to DB for setup");
+
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("connection error: {}", e);
+        }
+    });
+
+    client
+        .execute(
+            &format!(
+                r#"
+        CREATE TABLE IF NOT EXISTS {table} (
+            {id_col} SERIAL PRIMARY KEY,
+            {json_col} JSONB UNIQUE NOT NULL
+        )
+        "#
+            ),
+            &[],
+        )
+        .await
+        .expect("Failed to create table");
+
+    Db::new(
+        &conn_str,
+        &table,
+        &id_col,
+        &json_col,
+        pool_size,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        false,
+        None,
+        None,
     )
     .await
     .expect("Failed to connect to DB")
@@ -304,3 +365,46 @@ async fn test_batch_order_preservation_stress() {
     let unique_dupe_ids: HashSet<_> = dupe_ids.iter().collect();
     assert_eq!(unique_dupe_ids.len(), 3);
 }
+
+#[tokio::test]
+#[ignore]
+#[cfg(feature = "buffered-writer")]
+async fn test_buffered_writer_flush_resolves_repeated_value_to_the_right_id() {
+    // Regression test: a flush batch containing the same already-registered
+    // value more than once must still resolve every responder to the id
+    // matching its own request, not some other responder's.
+    let db = std::sync::Arc::new(create_db("writer_dedup").await);
+    let timestamp = get_timestamp();
+
+    let pre_existing = json!({"type": "writer_dedup", "value": "existing", "timestamp": timestamp});
+    let pre_existing_id = db
+        .register_batch_objects(&[pre_existing.to_string()])
+        .await
+        .unwrap()[0];
+
+    let config = BufferedWriterConfig {
+        buffer_size: 3,
+        max_latency: Duration::from_secs(30),
+        channel_capacity: 16,
+    };
+    let (handle, _join) = spawn_buffered_writer(db, config);
+
+    let h1 = handle.clone();
+    let h2 = handle.clone();
+    let h3 = handle.clone();
+    let pre_existing_str = pre_existing.to_string();
+    let new_object = json!({"type": "writer_dedup", "value": "new", "timestamp": timestamp}).to_string();
+    let (id1, id2, id3) = tokio::time::timeout(Duration::from_secs(5), async move {
+        tokio::join!(
+            h1.register_object(pre_existing_str.clone()),
+            h2.register_object(pre_existing_str),
+            h3.register_object(new_object),
+        )
+    })
+    .await
+    .expect("batch should flush once buffer_size is reached");
+
+    assert_eq!(id1.unwrap(), pre_existing_id);
+    assert_eq!(id2.unwrap(), pre_existing_id);
+    assert_ne!(id3.unwrap(), pre_existing_id);
+}